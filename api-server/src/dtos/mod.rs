@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// DTO pour créer une nouvelle tâche
 #[derive(Debug, Deserialize, Serialize)]
@@ -9,10 +10,39 @@ pub struct CreateTaskDto {
     pub original_name: String,
     pub mime_type: String,
     pub options: TaskOptionsDto,
+    #[serde(default)]
+    pub priority: Option<String>,  // "high", "normal" (défaut), "low"
+    #[serde(default)]
+    pub run_at: Option<String>,  // RFC 3339, exclusif avec delay_seconds
+    #[serde(default)]
+    pub delay_seconds: Option<u64>,
+    /// Durée maximale d'une tentative (défaut : celui du type de tâche)
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    /// RFC 3339 : la tâche est abandonnée (expired) si elle n'a pas commencé
+    /// avant, exclusif avec expires_in_seconds
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub expires_in_seconds: Option<u64>,
+    /// Tâches qui doivent réussir avant que celle-ci soit enfilée
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Dépendance dont la sortie sert de fichier d'entrée (file_path peut alors être vide)
+    #[serde(default)]
+    pub input_from: Option<String>,
+    #[serde(default)]
+    pub on_dependency_failure: Option<String>,  // "cancel" (défaut), "fail"
+    /// Job auquel rattacher la tâche
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// Alternative à l'en-tête `Idempotency-Key`
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Options de traitement
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct TaskOptionsDto {
     // Video options
     pub video_codec: Option<String>,
@@ -36,13 +66,81 @@ pub struct TaskResponse {
     pub id: String,
     pub task_type: String,
     pub status: String,
+    pub priority: String,
     pub progress: f32,
     pub error: Option<String>,
     pub output_path: Option<String>,
+    pub run_at: Option<String>,
+    pub retry_count: u32,
+    pub next_retry_at: Option<String>,
+    /// Timeout effectif d'une tentative
+    pub timeout_seconds: u64,
+    pub expires_at: Option<String>,
+    pub depends_on: Vec<String>,
+    pub job_id: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Historique des transitions de statut d'une tâche
+#[derive(Debug, Serialize)]
+pub struct TaskHistoryResponse {
+    pub task_id: String,
+    pub status: String,
+    pub transitions: Vec<TransitionResponse>,
+}
+
+/// Une transition de statut, de la plus ancienne à la plus récente
+#[derive(Debug, Serialize)]
+pub struct TransitionResponse {
+    pub from: String,
+    pub to: String,
+    pub at: String,
+    pub worker_id: Option<String>,
+    pub attempt: u32,
+    pub error: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Réponse pour une entrée de la dead-letter queue
+#[derive(Debug, Serialize)]
+pub struct DeadLetterResponse {
+    pub id: String,
+    pub task_type: String,
+    pub reason: String,
+    pub errors: Vec<String>,
+    pub dead_lettered_at: String,
+    pub task: Option<TaskResponse>,
+    pub payload: String,
+}
+
+/// DTO pour rejouer une entrée de la dead-letter queue (tous les champs sont optionnels)
+#[derive(Debug, Default, Deserialize)]
+pub struct ReplayDeadLetterDto {
+    pub options: Option<TaskOptionsDto>,
+    pub priority: Option<String>,
+    pub max_retries: Option<u32>,
+    /// Utile pour rejouer une tâche qui a échoué sur timeout
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Réponse pour un worker du registre
+#[derive(Debug, Serialize)]
+pub struct WorkerResponse {
+    pub worker_id: String,
+    pub task_types: Vec<String>,
+    pub hostname: String,
+    pub version: String,
+    pub started_at: String,
+    pub last_heartbeat: String,
+    pub concurrency: usize,
+    pub current_task_ids: Vec<String>,
+    pub processed_count: u64,
+    pub failed_count: u64,
+    /// Faux si le worker a manqué plusieurs heartbeats
+    pub alive: bool,
+}
+
 /// Réponse pour la création d'une tâche
 #[derive(Debug, Serialize)]
 pub struct CreateTaskResponse {
@@ -51,6 +149,114 @@ pub struct CreateTaskResponse {
     pub message: String,
 }
 
+/// Résultat d'un élément de `POST /tasks/batch`
+#[derive(Debug, Serialize)]
+pub struct BatchItemResult {
+    /// Position de l'élément dans le lot
+    pub index: usize,
+    pub task_id: Option<String>,
+    /// "created", "replayed" (clé d'idempotence déjà utilisée) ou "failed"
+    pub status: String,
+    pub error: Option<String>,
+}
+
+impl BatchItemResult {
+    pub fn created(index: usize, task_id: String) -> Self {
+        Self { index, task_id: Some(task_id), status: "created".to_string(), error: None }
+    }
+    
+    pub fn replayed(index: usize, task_id: String) -> Self {
+        Self { index, task_id: Some(task_id), status: "replayed".to_string(), error: None }
+    }
+    
+    pub fn failed(index: usize, error: String) -> Self {
+        Self { index, task_id: None, status: "failed".to_string(), error: Some(error) }
+    }
+}
+
+/// Réponse de `POST /tasks/batch`
+#[derive(Debug, Serialize)]
+pub struct BatchCreateResponse {
+    /// Vrai si tous les éléments ont été acceptés
+    pub success: bool,
+    /// Tâches nouvellement créées (hors rejouées)
+    pub created: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+/// DTO pour créer un job et ses tâches en une requête
+#[derive(Debug, Deserialize)]
+pub struct CreateJobDto {
+    pub name: Option<String>,
+    pub tasks: Vec<CreateTaskDto>,
+}
+
+/// Réponse de `POST /jobs`
+#[derive(Debug, Serialize)]
+pub struct CreateJobResponse {
+    /// Vrai si toutes les tâches ont été acceptées
+    pub success: bool,
+    pub job_id: String,
+    pub tasks: Vec<BatchItemResult>,
+}
+
+/// Réponse pour un job, avec son statut agrégé et ses tâches
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub id: String,
+    pub name: Option<String>,
+    pub status: String,
+    /// Progression combinée des tâches (0.0 - 1.0)
+    pub progress: f32,
+    /// Nombre de tâches par statut
+    pub task_counts: HashMap<String, u64>,
+    pub created_at: String,
+    pub cancelled_at: Option<String>,
+    pub tasks: Vec<TaskResponse>,
+}
+
+/// Résultat de l'annulation d'un job
+#[derive(Debug, Serialize)]
+pub struct CancelJobResponse {
+    pub job_id: String,
+    /// Tâches dont l'annulation a été demandée
+    pub cancelled: Vec<String>,
+    /// Tâches déjà terminées ou dont l'annulation a échoué
+    pub skipped: Vec<String>,
+}
+
+/// DTO pour exécuter un workflow sur un fichier d'entrée
+#[derive(Debug, Deserialize)]
+pub struct WorkflowRunDto {
+    pub file_path: String,
+    pub file_size: u64,
+    pub original_name: String,
+    pub mime_type: String,
+    /// Remplace la priorité des étapes
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Nom du job créé (par défaut "workflow:{name}")
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Réponse de `POST /workflows/:name/runs`
+#[derive(Debug, Serialize)]
+pub struct WorkflowRunResponse {
+    pub workflow: String,
+    /// Job regroupant les tâches de l'exécution
+    pub job_id: String,
+    /// Une tâche par étape, dans l'ordre du modèle
+    pub tasks: Vec<WorkflowStepTask>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowStepTask {
+    pub step: String,
+    pub task_id: String,
+}
+
 /// Réponse API générique
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
@@ -68,6 +274,7 @@ impl<T> ApiResponse<T> {
         }
     }
 
+    #[allow(dead_code)]
     pub fn error(message: String) -> Self {
         Self {
             success: false,
@@ -4,6 +4,8 @@ use axum::{
     Json,
 };
 use serde_json::json;
+use shared::dependencies::DependencyError;
+use shared::InvalidTransition;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,6 +16,18 @@ pub enum ApiError {
     #[error("Task not found: {0}")]
     TaskNotFound(String),
     
+    #[error("Job not found: {0}")]
+    JobNotFound(String),
+    
+    #[error("Workflow not found: {0}")]
+    WorkflowNotFound(String),
+    
+    #[error("Dead-letter entry not found: {0}")]
+    DeadLetterNotFound(String),
+    
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
     #[error("Database error: {0}")]
     DatabaseError(#[from] mongodb::error::Error),
     
@@ -27,11 +41,31 @@ pub enum ApiError {
     SerializationError(#[from] serde_json::Error),
 }
 
+impl From<InvalidTransition> for ApiError {
+    fn from(err: InvalidTransition) -> Self {
+        ApiError::Conflict(err.to_string())
+    }
+}
+
+impl From<DependencyError> for ApiError {
+    fn from(err: DependencyError) -> Self {
+        match err {
+            DependencyError::Mongo(err) => ApiError::DatabaseError(err),
+            DependencyError::Redis(err) => ApiError::RedisError(err),
+            err => ApiError::InternalError(err.to_string()),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             ApiError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::TaskNotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::JobNotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::WorkflowNotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::DeadLetterNotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             ApiError::DatabaseError(err) => {
                 tracing::error!("Database error: {}", err);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error occurred".to_string())
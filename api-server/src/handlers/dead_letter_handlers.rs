@@ -0,0 +1,82 @@
+use crate::dtos::{ApiResponse, CreateTaskResponse, DeadLetterResponse, ReplayDeadLetterDto};
+use crate::error::ApiError;
+use crate::services;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct ListDeadLettersQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+pub async fn list_dead_letters(
+    State(state): State<Arc<AppState>>,
+    Path(task_type): Path<String>,
+    Query(query): Query<ListDeadLettersQuery>,
+) -> Result<Json<ApiResponse<Vec<DeadLetterResponse>>>, ApiError> {
+    tracing::debug!("Listing dead-letter queue: {}", task_type);
+    
+    let entries = services::list_dead_letters(&state, &task_type, query.limit).await?;
+    
+    Ok(Json(ApiResponse::success(entries)))
+}
+
+pub async fn get_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path((task_type, id)): Path<(String, String)>,
+) -> Result<Json<ApiResponse<DeadLetterResponse>>, ApiError> {
+    tracing::debug!("Getting dead-letter entry: {}/{}", task_type, id);
+    
+    let entry = services::get_dead_letter(&state, &task_type, &id).await?;
+    
+    Ok(Json(ApiResponse::success(entry)))
+}
+
+pub async fn replay_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path((task_type, id)): Path<(String, String)>,
+    dto: Option<Json<ReplayDeadLetterDto>>,
+) -> Result<Json<CreateTaskResponse>, ApiError> {
+    tracing::info!("Replaying dead-letter entry: {}/{}", task_type, id);
+    
+    let dto = dto.map(|Json(dto)| dto).unwrap_or_default();
+    let task_id = services::replay_dead_letter(&state, &task_type, &id, dto).await?;
+    
+    Ok(Json(CreateTaskResponse {
+        success: true,
+        task_id,
+        message: "Dead-letter task replayed and queued successfully".to_string(),
+    }))
+}
+
+pub async fn purge_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path((task_type, id)): Path<(String, String)>,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    tracing::info!("Purging dead-letter entry: {}/{}", task_type, id);
+    
+    services::purge_dead_letter(&state, &task_type, &id).await?;
+    
+    Ok(Json(ApiResponse::success("Dead-letter entry purged".to_string())))
+}
+
+pub async fn purge_dead_letters(
+    State(state): State<Arc<AppState>>,
+    Path(task_type): Path<String>,
+) -> Result<Json<ApiResponse<u64>>, ApiError> {
+    tracing::info!("Purging dead-letter queue: {}", task_type);
+    
+    let count = services::purge_dead_letters(&state, &task_type).await?;
+    
+    Ok(Json(ApiResponse::success(count)))
+}
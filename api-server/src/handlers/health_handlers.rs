@@ -35,7 +35,7 @@ async fn check_mongo(state: &AppState) -> bool {
 }
 
 async fn check_redis(state: &AppState) -> bool {
-    let mut conn = match state.redis_client.get_async_connection().await {
+    let mut conn = match state.redis_client.get_multiplexed_async_connection().await {
         Ok(conn) => conn,
         Err(_) => return false,
     };
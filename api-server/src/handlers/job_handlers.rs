@@ -0,0 +1,42 @@
+use crate::dtos::{ApiResponse, CancelJobResponse, CreateJobDto, CreateJobResponse, JobResponse};
+use crate::error::ApiError;
+use crate::services;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+pub async fn create_job(
+    State(state): State<Arc<AppState>>,
+    Json(dto): Json<CreateJobDto>,
+) -> Result<Json<CreateJobResponse>, ApiError> {
+    tracing::info!("Creating job with {} tasks", dto.tasks.len());
+    
+    let job = services::create_job(&state, dto).await?;
+    
+    Ok(Json(job))
+}
+
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Result<Json<ApiResponse<JobResponse>>, ApiError> {
+    tracing::debug!("Getting job: {}", job_id);
+    
+    let job = services::get_job(&state, &job_id).await?;
+    
+    Ok(Json(ApiResponse::success(job)))
+}
+
+pub async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Result<Json<ApiResponse<CancelJobResponse>>, ApiError> {
+    tracing::info!("Cancelling job: {}", job_id);
+    
+    let result = services::cancel_job(&state, &job_id).await?;
+    
+    Ok(Json(ApiResponse::success(result)))
+}
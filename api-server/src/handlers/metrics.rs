@@ -10,6 +10,7 @@ pub async fn get_metrics(State(state): State<Arc<AppState>>) -> String {
     let completed = state.metrics.get_completed();
     let failed = state.metrics.get_failed();
     let cancelled = state.metrics.get_cancelled();
+    let expired = state.metrics.get_expired();
     
     format!(
         "# HELP tasks_total Total number of tasks by status\n\
@@ -17,8 +18,9 @@ pub async fn get_metrics(State(state): State<Arc<AppState>>) -> String {
          tasks_total{{status=\"created\"}} {}\n\
          tasks_total{{status=\"completed\"}} {}\n\
          tasks_total{{status=\"failed\"}} {}\n\
-         tasks_total{{status=\"cancelled\"}} {}\n",
-        created, completed, failed, cancelled
+         tasks_total{{status=\"cancelled\"}} {}\n\
+         tasks_total{{status=\"expired\"}} {}\n",
+        created, completed, failed, cancelled, expired
     )
 }
 
@@ -31,7 +33,8 @@ pub async fn reset_metrics(State(state): State<Arc<AppState>>) -> Json<serde_jso
         "created": 0,
         "completed": 0,
         "failed": 0,
-        "cancelled": 0
+        "cancelled": 0,
+        "expired": 0
     }))
 }
 
@@ -60,18 +63,25 @@ pub async fn sync_metrics(State(state): State<Arc<AppState>>) -> Json<serde_json
         .await
         .unwrap_or(0);
     
+    let expired = collection
+        .count_documents(mongodb::bson::doc! { "status": "expired" }, None)
+        .await
+        .unwrap_or(0);
+    
     // Reset puis set aux valeurs de la DB
     state.metrics.reset_all();
     state.metrics.set_created(created);
     state.metrics.set_completed(completed);
     state.metrics.set_failed(failed);
     state.metrics.set_cancelled(cancelled);
+    state.metrics.set_expired(expired);
     
     Json(json!({
         "message": "Metrics synchronized with MongoDB",
         "created": created,
         "completed": completed,
         "failed": failed,
-        "cancelled": cancelled
+        "cancelled": cancelled,
+        "expired": expired
     }))
 }
@@ -1,7 +1,15 @@
 pub mod task_handlers;
+pub mod dead_letter_handlers;
+pub mod worker_handlers;
+pub mod job_handlers;
+pub mod workflow_handlers;
 pub mod health_handlers;
 pub mod metrics;
 
 pub use task_handlers::*;
+pub use dead_letter_handlers::*;
+pub use worker_handlers::*;
+pub use job_handlers::*;
+pub use workflow_handlers::*;
 pub use health_handlers::*;
 pub use metrics::*;
@@ -1,26 +1,66 @@
-use crate::dtos::{ApiResponse, CreateTaskDto, CreateTaskResponse, TaskResponse};
+use crate::dtos::{ApiResponse, BatchCreateResponse, CreateTaskDto, CreateTaskResponse, TaskHistoryResponse, TaskResponse};
 use crate::error::ApiError;
 use crate::services;
 use crate::state::AppState;
 use axum::{
     extract::{Path, Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
+use futures::{Stream, StreamExt};
 use serde::Deserialize;
 use std::sync::Arc;
 
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
 pub async fn create_task(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(dto): Json<CreateTaskDto>,
 ) -> Result<Json<CreateTaskResponse>, ApiError> {
     tracing::info!("Creating task: {:?}", dto.task_type);
     
-    let task_id = services::create_task(&state, dto).await?;
+    let idempotency_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map(str::to_string)
+                .map_err(|_| ApiError::InvalidInput("Invalid Idempotency-Key header".to_string()))
+        })
+        .transpose()?;
+    
+    let created = services::create_task(&state, dto, idempotency_key).await?;
+    
+    let message = if created.replayed {
+        "Task already created for this idempotency key"
+    } else {
+        "Task created and queued successfully"
+    };
     
     Ok(Json(CreateTaskResponse {
         success: true,
-        task_id,
-        message: "Task created and queued successfully".to_string(),
+        task_id: created.task_id,
+        message: message.to_string(),
+    }))
+}
+
+pub async fn create_tasks_batch(
+    State(state): State<Arc<AppState>>,
+    Json(dtos): Json<Vec<CreateTaskDto>>,
+) -> Result<Json<BatchCreateResponse>, ApiError> {
+    tracing::info!("Creating task batch of {}", dtos.len());
+    
+    let results = services::create_tasks_batch(&state, dtos).await?;
+    let failed = results.iter().filter(|result| result.error.is_some()).count();
+    let created = results.iter().filter(|result| result.status == "created").count();
+    
+    Ok(Json(BatchCreateResponse {
+        success: failed == 0,
+        created,
+        failed,
+        results,
     }))
 }
 
@@ -35,9 +75,21 @@ pub async fn get_task(
     Ok(Json(ApiResponse::success(task)))
 }
 
+pub async fn get_task_history(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<ApiResponse<TaskHistoryResponse>>, ApiError> {
+    tracing::debug!("Getting task history: {}", task_id);
+    
+    let history = services::get_task_history(&state, &task_id).await?;
+    
+    Ok(Json(ApiResponse::success(history)))
+}
+
 #[derive(Deserialize)]
 pub struct ListTasksQuery {
     pub status: Option<String>,
+    pub job_id: Option<String>,
     #[serde(default = "default_limit")]
     pub limit: i64,
     #[serde(default)]
@@ -54,7 +106,7 @@ pub async fn list_tasks(
 ) -> Result<Json<ApiResponse<Vec<TaskResponse>>>, ApiError> {
     tracing::debug!("Listing tasks with filters: {:?}", query.status);
     
-    let tasks = services::list_tasks(&state, query.status, query.limit, query.skip).await?;
+    let tasks = services::list_tasks(&state, query.status, query.job_id, query.limit, query.skip).await?;
     
     Ok(Json(ApiResponse::success(tasks)))
 }
@@ -73,3 +125,37 @@ pub async fn cancel_task(
         message: None,
     }))
 }
+
+pub async fn pause_task(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    tracing::info!("Pausing task: {}", task_id);
+    
+    let status = services::pause_task(&state, &task_id).await?;
+    
+    Ok(Json(ApiResponse::success(status.to_string())))
+}
+
+pub async fn resume_task(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    tracing::info!("Resuming task: {}", task_id);
+    
+    let status = services::resume_task(&state, &task_id).await?;
+    
+    Ok(Json(ApiResponse::success(status.to_string())))
+}
+
+/// Événements de la tâche en Server-Sent Events, au format JSON versionné
+pub async fn task_events(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, serde_json::Error>>>, ApiError> {
+    let events = services::task_events(&state, &task_id).await?;
+    
+    let stream = events.map(|event| Ok(Event::default().event(event.kind()).data(event.to_json()?)));
+    
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
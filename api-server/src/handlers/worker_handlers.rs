@@ -0,0 +1,16 @@
+use crate::dtos::{ApiResponse, WorkerResponse};
+use crate::error::ApiError;
+use crate::services;
+use crate::state::AppState;
+use axum::{extract::State, Json};
+use std::sync::Arc;
+
+pub async fn list_workers(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ApiResponse<Vec<WorkerResponse>>>, ApiError> {
+    tracing::debug!("Listing workers");
+    
+    let workers = services::list_workers(&state).await?;
+    
+    Ok(Json(ApiResponse::success(workers)))
+}
@@ -0,0 +1,43 @@
+use crate::dtos::{ApiResponse, WorkflowRunDto, WorkflowRunResponse};
+use crate::error::ApiError;
+use crate::services;
+use crate::state::AppState;
+use crate::workflows::WorkflowTemplate;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+pub async fn list_workflows(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ApiResponse<Vec<WorkflowTemplate>>>, ApiError> {
+    tracing::debug!("Listing workflows");
+    
+    let workflows = services::list_workflows(&state);
+    
+    Ok(Json(ApiResponse::success(workflows)))
+}
+
+pub async fn get_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<WorkflowTemplate>>, ApiError> {
+    tracing::debug!("Getting workflow: {}", name);
+    
+    let workflow = services::get_workflow(&state, &name)?;
+    
+    Ok(Json(ApiResponse::success(workflow)))
+}
+
+pub async fn run_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(dto): Json<WorkflowRunDto>,
+) -> Result<Json<ApiResponse<WorkflowRunResponse>>, ApiError> {
+    tracing::info!("Running workflow {} on {}", name, dto.file_path);
+    
+    let run = services::run_workflow(&state, &name, dto).await?;
+    
+    Ok(Json(ApiResponse::success(run)))
+}
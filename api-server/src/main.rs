@@ -4,6 +4,7 @@ mod handlers;
 mod routes;
 mod services;
 mod state;
+mod workflows;
 
 use state::AppState;
 use std::sync::Arc;
@@ -29,20 +30,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap_or_else(|_| "redis://localhost:6379".to_string());
     let database_name = std::env::var("MONGODB_DATABASE")
         .unwrap_or_else(|_| "distributed_media_queue".to_string());
+    let workflows_dir = std::env::var("WORKFLOWS_DIR")
+        .unwrap_or_else(|_| "./workflows".to_string());
     
     tracing::info!("Starting API Server...");
     tracing::info!("MongoDB URI: {}", mongo_uri);
     tracing::info!("Redis URI: {}", redis_uri);
     tracing::info!("Database: {}", database_name);
     
+    let workflows = workflows::load_templates(std::path::Path::new(&workflows_dir))
+        .expect("Failed to load workflow templates");
+    
+    tracing::info!("{} workflow templates loaded from {}", workflows.len(), workflows_dir);
+    
     let state = Arc::new(
-        AppState::new(&mongo_uri, &redis_uri, database_name)
+        AppState::new(&mongo_uri, &redis_uri, database_name, workflows)
             .await
             .expect("Failed to initialize application state"),
     );
     
     tracing::info!("Application state initialized");
     
+    shared::utils::mongo_utils::ensure_indexes(&state.get_database())
+        .await
+        .expect("Failed to create MongoDB indexes");
+    
+    tokio::spawn(services::event_service::track_worker_metrics(state.clone()));
+    
     let app = routes::create_router(state);
     
     let addr = format!("{}:{}", host, port);
@@ -55,12 +69,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("📋 API endpoints:");
     tracing::info!("  POST   /tasks       - Create task");
     tracing::info!("  GET    /tasks       - List tasks");
+    tracing::info!("  POST   /tasks/batch - Create tasks in bulk");
     tracing::info!("  GET    /tasks/:id   - Get task");
     tracing::info!("  DELETE /tasks/:id   - Cancel task");
+    tracing::info!("  GET    /tasks/:id/history - Get task status history");
+    tracing::info!("  GET    /tasks/:id/events  - Stream task events (SSE)");
+    tracing::info!("  POST   /tasks/:id/pause  - Pause task");
+    tracing::info!("  POST   /tasks/:id/resume - Resume task");
+    tracing::info!("  GET    /dlq/:type   - List dead-letter queue");
+    tracing::info!("  DELETE /dlq/:type   - Purge dead-letter queue");
+    tracing::info!("  GET    /dlq/:type/:id        - Inspect dead-letter entry");
+    tracing::info!("  POST   /dlq/:type/:id/replay - Replay dead-letter entry");
+    tracing::info!("  DELETE /dlq/:type/:id        - Purge dead-letter entry");
+    tracing::info!("  POST   /jobs        - Create job with its tasks");
+    tracing::info!("  GET    /jobs/:id    - Get job status and tasks");
+    tracing::info!("  DELETE /jobs/:id    - Cancel all tasks of a job");
+    tracing::info!("  GET    /workflows   - List workflow templates");
+    tracing::info!("  GET    /workflows/:name      - Get workflow template");
+    tracing::info!("  POST   /workflows/:name/runs - Run workflow on an input file");
+    tracing::info!("  GET    /workers     - List registered workers");
     
     axum::serve(listener, app)
+        .with_graceful_shutdown(shared::utils::shutdown_signal())
         .await
         .expect("Failed to start server");
     
+    tracing::info!("API Server stopped");
+    
     Ok(())
 }
@@ -15,8 +15,25 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/metrics/sync", get(handlers::sync_metrics))
         .route("/tasks", post(handlers::create_task))
         .route("/tasks", get(handlers::list_tasks))
+        .route("/tasks/batch", post(handlers::create_tasks_batch))
         .route("/tasks/:id", get(handlers::get_task))
         .route("/tasks/:id", delete(handlers::cancel_task))
+        .route("/tasks/:id/history", get(handlers::get_task_history))
+        .route("/tasks/:id/events", get(handlers::task_events))
+        .route("/tasks/:id/pause", post(handlers::pause_task))
+        .route("/tasks/:id/resume", post(handlers::resume_task))
+        .route("/dlq/:task_type", get(handlers::list_dead_letters))
+        .route("/dlq/:task_type", delete(handlers::purge_dead_letters))
+        .route("/dlq/:task_type/:id", get(handlers::get_dead_letter))
+        .route("/dlq/:task_type/:id", delete(handlers::purge_dead_letter))
+        .route("/dlq/:task_type/:id/replay", post(handlers::replay_dead_letter))
+        .route("/jobs", post(handlers::create_job))
+        .route("/jobs/:id", get(handlers::get_job))
+        .route("/jobs/:id", delete(handlers::cancel_job))
+        .route("/workflows", get(handlers::list_workflows))
+        .route("/workflows/:name", get(handlers::get_workflow))
+        .route("/workflows/:name/runs", post(handlers::run_workflow))
+        .route("/workers", get(handlers::list_workers))
         .with_state(state)
         .layer(TraceLayer::new_for_http())
 }
@@ -0,0 +1,208 @@
+use crate::dtos::{DeadLetterResponse, ReplayDeadLetterDto};
+use crate::error::ApiError;
+use crate::services::event_service::publish_event;
+use crate::services::task_service::{add_options_to_metadata, parse_priority, parse_task_type, task_to_response, validate_timeout};
+use crate::state::AppState;
+use shared::utils::mongo_utils::{task_update, transition_filter};
+use shared::{queue, DeadLetter, Task, TaskEvent, TaskStatus, TaskType};
+use std::collections::HashMap;
+
+pub async fn list_dead_letters(
+    state: &AppState,
+    task_type: &str,
+    limit: usize,
+) -> Result<Vec<DeadLetterResponse>, ApiError> {
+    let task_type = parse_task_type(task_type)?;
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    let entries: HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(queue::dead_letter_key(&task_type))
+        .query_async(&mut conn)
+        .await?;
+    
+    let mut entries: Vec<DeadLetter> = entries
+        .values()
+        .filter_map(|json| serde_json::from_str(json).ok())
+        .collect();
+    
+    // Les plus récentes d'abord
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.dead_lettered_at));
+    
+    Ok(entries
+        .into_iter()
+        .take(limit)
+        .map(dead_letter_to_response)
+        .collect())
+}
+
+pub async fn get_dead_letter(
+    state: &AppState,
+    task_type: &str,
+    id: &str,
+) -> Result<DeadLetterResponse, ApiError> {
+    let task_type = parse_task_type(task_type)?;
+    let entry = fetch_dead_letter(state, &task_type, id).await?;
+    
+    Ok(dead_letter_to_response(entry))
+}
+
+pub async fn replay_dead_letter(
+    state: &AppState,
+    task_type: &str,
+    id: &str,
+    dto: ReplayDeadLetterDto,
+) -> Result<String, ApiError> {
+    let task_type = parse_task_type(task_type)?;
+    let entry = fetch_dead_letter(state, &task_type, id).await?;
+    
+    let mut task = entry.task.ok_or_else(|| {
+        ApiError::InvalidInput(format!("Dead-letter entry {} has no readable task to replay", id))
+    })?;
+    
+    // 1. Appliquer les modifications demandées
+    if let Some(ref options) = dto.options {
+        if let Some(quality) = options.quality {
+            if quality > 100 {
+                return Err(ApiError::InvalidInput(
+                    "Image quality must be between 0-100".to_string()
+                ));
+            }
+        }
+        add_options_to_metadata(&mut task.media.metadata, options);
+    }
+    if dto.priority.is_some() {
+        task.priority = parse_priority(dto.priority.as_deref())?;
+    }
+    if let Some(max_retries) = dto.max_retries {
+        task.max_retries = max_retries;
+    }
+    if dto.timeout_seconds.is_some() {
+        validate_timeout(dto.timeout_seconds)?;
+        task.timeout_seconds = dto.timeout_seconds;
+    }
+    
+    // 2. Repartir d'une tâche fraîche, l'historique d'erreurs est conservé
+    task.retry_count = 0;
+    task.next_retry_at = None;
+    task.progress = 0.0;
+    task.error = None;
+    task.output_path = None;
+    task.completed_at = None;
+    task.update_status(TaskStatus::Queued)?;
+    
+    // 3. Sauvegarder MongoDB
+    let db = state.get_database();
+    let collection = db.collection::<Task>("tasks");
+    let update = task_update(&task)
+        .map_err(|e| ApiError::InternalError(format!("Failed to serialize task: {}", e)))?;
+    
+    let result = collection
+        .update_one(
+            transition_filter(&task.id, &task.status),
+            update,
+            None,
+        )
+        .await?;
+    
+    if result.matched_count == 0 {
+        return Err(ApiError::Conflict(
+            format!("Task {} is no longer failed", task.id)
+        ));
+    }
+    
+    // 4. Retirer de la DLQ et enfiler atomiquement
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    let replayed: i32 = queue::replay_script()
+        .key(queue::dead_letter_key(&task_type))
+        .key(queue::lane_key(&task_type, task.priority))
+        .key(queue::notify_key(&task_type))
+        .arg(id)
+        .arg(&task.id)
+        .invoke_async(&mut conn)
+        .await?;
+    
+    if replayed == 0 {
+        // Rejouée ou purgée en parallèle
+        return Err(ApiError::DeadLetterNotFound(id.to_string()));
+    }
+    
+    tracing::info!(task_id = %task.id, "Dead-letter task replayed");
+    publish_event(state, TaskEvent::queued(&task.id)).await;
+    
+    Ok(task.id)
+}
+
+pub async fn purge_dead_letter(
+    state: &AppState,
+    task_type: &str,
+    id: &str,
+) -> Result<(), ApiError> {
+    let task_type = parse_task_type(task_type)?;
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    let removed: i32 = redis::cmd("HDEL")
+        .arg(queue::dead_letter_key(&task_type))
+        .arg(id)
+        .query_async(&mut conn)
+        .await?;
+    
+    if removed == 0 {
+        return Err(ApiError::DeadLetterNotFound(id.to_string()));
+    }
+    
+    tracing::info!(dead_letter_id = %id, "Dead-letter entry purged");
+    
+    Ok(())
+}
+
+pub async fn purge_dead_letters(
+    state: &AppState,
+    task_type: &str,
+) -> Result<u64, ApiError> {
+    let task_type = parse_task_type(task_type)?;
+    let key = queue::dead_letter_key(&task_type);
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    let (count,): (u64,) = redis::pipe()
+        .atomic()
+        .hlen(&key)
+        .del(&key)
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+    
+    tracing::info!(task_type = %task_type, count = count, "Dead-letter queue purged");
+    
+    Ok(count)
+}
+
+async fn fetch_dead_letter(
+    state: &AppState,
+    task_type: &TaskType,
+    id: &str,
+) -> Result<DeadLetter, ApiError> {
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    let entry: Option<String> = redis::cmd("HGET")
+        .arg(queue::dead_letter_key(task_type))
+        .arg(id)
+        .query_async(&mut conn)
+        .await?;
+    
+    let entry = entry.ok_or_else(|| ApiError::DeadLetterNotFound(id.to_string()))?;
+    
+    Ok(serde_json::from_str(&entry)?)
+}
+
+fn dead_letter_to_response(entry: DeadLetter) -> DeadLetterResponse {
+    DeadLetterResponse {
+        id: entry.id,
+        task_type: entry.task_type.to_string(),
+        reason: entry.reason.to_string(),
+        errors: entry.errors,
+        dead_lettered_at: entry.dead_lettered_at.to_rfc3339(),
+        task: entry.task.map(task_to_response),
+        payload: entry.payload,
+    }
+}
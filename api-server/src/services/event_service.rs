@@ -0,0 +1,66 @@
+use crate::error::ApiError;
+use crate::state::AppState;
+use futures::{Stream, StreamExt};
+use shared::pubsub::EVENTS_CHANNEL;
+use shared::{Task, TaskEvent};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Publie un événement de tâche. Un échec est seulement journalisé : les
+/// événements informent, MongoDB reste la référence.
+pub async fn publish_event(state: &AppState, event: TaskEvent) {
+    if let Err(e) = state.pubsub.publish_event(&event).await {
+        tracing::warn!(task_id = %event.task_id(), event = event.kind(), error = %e, "Failed to publish task event");
+    }
+}
+
+/// Publie plusieurs événements en un seul pipeline, avec la même tolérance
+pub async fn publish_events(state: &AppState, events: Vec<TaskEvent>) {
+    if let Err(e) = state.pubsub.publish_events(&events).await {
+        tracing::warn!(count = events.len(), error = %e, "Failed to publish task events");
+    }
+}
+
+/// Flux des événements d'une tâche existante
+pub async fn task_events(
+    state: &AppState,
+    task_id: &str,
+) -> Result<impl Stream<Item = TaskEvent> + Send, ApiError> {
+    let collection = state.get_database().collection::<Task>("tasks");
+    collection
+        .find_one(mongodb::bson::doc! { "task_id": task_id }, None)
+        .await?
+        .ok_or_else(|| ApiError::TaskNotFound(task_id.to_string()))?;
+    
+    let events = state
+        .pubsub
+        .subscribe_events(vec![EVENTS_CHANNEL.to_string()])
+        .await
+        .map_err(|e| ApiError::InternalError(format!("Failed to subscribe to task events: {}", e)))?;
+    
+    let task_id = task_id.to_string();
+    Ok(events.filter(move |event| futures::future::ready(event.task_id() == task_id)))
+}
+
+/// Tient à jour les métriques alimentées par les workers à partir du canal
+/// global ; l'abonnement est rétabli s'il tombe
+pub async fn track_worker_metrics(state: Arc<AppState>) {
+    loop {
+        match state.pubsub.subscribe_events(vec![EVENTS_CHANNEL.to_string()]).await {
+            Ok(events) => {
+                let mut events = Box::pin(events);
+                while let Some(event) = events.next().await {
+                    if let TaskEvent::Expired { .. } = event {
+                        state.metrics.increment_expired();
+                    }
+                }
+                tracing::warn!("Task event subscription for metrics closed, resubscribing");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to subscribe to task events for metrics");
+            }
+        }
+        
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
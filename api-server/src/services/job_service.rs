@@ -0,0 +1,175 @@
+use crate::dtos::{CancelJobResponse, CreateJobDto, CreateJobResponse, JobResponse};
+use crate::error::ApiError;
+use crate::services::task_service::{cancel_task, create_tasks_batch, task_to_response, MAX_BATCH_SIZE};
+use crate::state::AppState;
+use futures::stream::TryStreamExt;
+use shared::{Job, JobStatus, Task};
+use std::collections::{HashMap, HashSet};
+
+pub async fn create_job(
+    state: &AppState,
+    dto: CreateJobDto,
+) -> Result<CreateJobResponse, ApiError> {
+    // Valider le lot avant de créer le job, pour ne pas laisser de job vide
+    if dto.tasks.is_empty() || dto.tasks.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::InvalidInput(
+            format!("A job must contain between 1 and {} tasks", MAX_BATCH_SIZE)
+        ));
+    }
+    
+    let job = Job::new(dto.name);
+    let job_id = job.id.clone();
+    
+    state.get_database().collection::<Job>("jobs").insert_one(&job, None).await?;
+    
+    tracing::info!(job_id = %job_id, tasks = dto.tasks.len(), "Job created");
+    
+    let tasks = dto
+        .tasks
+        .into_iter()
+        .map(|mut task| {
+            task.job_id = Some(job_id.clone());
+            task
+        })
+        .collect();
+    
+    let results = create_tasks_batch(state, tasks).await?;
+    
+    Ok(CreateJobResponse {
+        success: results.iter().all(|result| result.error.is_none()),
+        job_id,
+        tasks: results,
+    })
+}
+
+pub async fn get_job(
+    state: &AppState,
+    job_id: &str,
+) -> Result<JobResponse, ApiError> {
+    let job = find_job(state, job_id).await?;
+    let tasks = job_tasks(state, job_id).await?;
+    
+    let mut task_counts = HashMap::new();
+    for task in &tasks {
+        *task_counts.entry(task.status.to_string()).or_insert(0) += 1;
+    }
+    
+    Ok(JobResponse {
+        status: JobStatus::aggregate(tasks.iter().map(|task| &task.status)).to_string(),
+        progress: Job::progress(&tasks),
+        task_counts,
+        id: job.id,
+        name: job.name,
+        created_at: job.created_at.to_rfc3339(),
+        cancelled_at: job.cancelled_at.map(|at| at.to_rfc3339()),
+        tasks: tasks.into_iter().map(task_to_response).collect(),
+    })
+}
+
+/// Annule toutes les tâches encore actives du job ; le job refuse ensuite
+/// toute nouvelle tâche
+pub async fn cancel_job(
+    state: &AppState,
+    job_id: &str,
+) -> Result<CancelJobResponse, ApiError> {
+    find_job(state, job_id).await?;
+    
+    let now = mongodb::bson::to_bson(&chrono::Utc::now())
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    state
+        .get_database()
+        .collection::<Job>("jobs")
+        .update_one(
+            mongodb::bson::doc! { "job_id": job_id, "cancelled_at": null },
+            mongodb::bson::doc! { "$set": { "cancelled_at": now } },
+            None,
+        )
+        .await?;
+    
+    let mut cancelled = Vec::new();
+    let mut skipped = Vec::new();
+    
+    for task in job_tasks(state, job_id).await? {
+        if task.status.is_terminal() {
+            skipped.push(task.id);
+            continue;
+        }
+        
+        match cancel_task(state, &task.id).await {
+            Ok(()) => cancelled.push(task.id),
+            Err(e) => {
+                tracing::warn!(job_id = %job_id, task_id = %task.id, error = %e, "Failed to cancel job task");
+                skipped.push(task.id);
+            }
+        }
+    }
+    
+    tracing::info!(job_id = %job_id, cancelled = cancelled.len(), skipped = skipped.len(), "Job cancelled");
+    
+    Ok(CancelJobResponse {
+        job_id: job_id.to_string(),
+        cancelled,
+        skipped,
+    })
+}
+
+/// Vérifie que le job existe et accepte encore des tâches
+pub(crate) async fn ensure_job_open(
+    state: &AppState,
+    job_id: &str,
+) -> Result<(), ApiError> {
+    let job = find_job(state, job_id).await?;
+    
+    if job.cancelled_at.is_some() {
+        return Err(ApiError::Conflict(format!("Job {} is cancelled", job_id)));
+    }
+    
+    Ok(())
+}
+
+/// Parmi `job_ids`, ceux des jobs existants et non annulés
+pub(crate) async fn open_job_ids(
+    state: &AppState,
+    job_ids: HashSet<&str>,
+) -> Result<HashSet<String>, ApiError> {
+    let filter = mongodb::bson::doc! {
+        "job_id": { "$in": job_ids.into_iter().collect::<Vec<_>>() },
+        "cancelled_at": null,
+    };
+    
+    let jobs: Vec<Job> = state
+        .get_database()
+        .collection::<Job>("jobs")
+        .find(filter, None)
+        .await?
+        .try_collect()
+        .await?;
+    
+    Ok(jobs.into_iter().map(|job| job.id).collect())
+}
+
+async fn find_job(state: &AppState, job_id: &str) -> Result<Job, ApiError> {
+    state
+        .get_database()
+        .collection::<Job>("jobs")
+        .find_one(mongodb::bson::doc! { "job_id": job_id }, None)
+        .await?
+        .ok_or_else(|| ApiError::JobNotFound(job_id.to_string()))
+}
+
+/// Tâches du job, dans l'ordre de création
+async fn job_tasks(state: &AppState, job_id: &str) -> Result<Vec<Task>, ApiError> {
+    let options = mongodb::options::FindOptions::builder()
+        .sort(mongodb::bson::doc! { "created_at": 1 })
+        .build();
+    
+    let tasks = state
+        .get_database()
+        .collection::<Task>("tasks")
+        .find(mongodb::bson::doc! { "job_id": job_id }, options)
+        .await?
+        .try_collect()
+        .await?;
+    
+    Ok(tasks)
+}
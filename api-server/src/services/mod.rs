@@ -1,3 +1,13 @@
 pub mod task_service;
+pub mod dead_letter_service;
+pub mod worker_service;
+pub mod job_service;
+pub mod workflow_service;
+pub mod event_service;
 
 pub use task_service::*;
+pub use dead_letter_service::*;
+pub use worker_service::*;
+pub use job_service::*;
+pub use workflow_service::*;
+pub use event_service::*;
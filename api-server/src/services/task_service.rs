@@ -1,19 +1,279 @@
-use crate::dtos::{CreateTaskDto, TaskOptionsDto, TaskResponse};
+use crate::dtos::{BatchItemResult, CreateTaskDto, TaskHistoryResponse, TaskOptionsDto, TaskResponse, TransitionResponse};
 use crate::error::ApiError;
+use crate::services::event_service::{publish_event, publish_events};
+use crate::services::job_service::{ensure_job_open, open_job_ids};
 use crate::state::AppState;
-use shared::{MediaFile, MediaType, Task, TaskStatus, TaskType};
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use shared::utils::mongo_utils::{is_duplicate_key_error, status_update, transition_filter};
+use shared::dependencies::{release_if_ready, settle_dependents};
+use shared::pubsub::cancel_channel;
+use shared::models::DependencyFailurePolicy;
+use shared::outbox::{self, OutboxEntry};
+use shared::{queue, MediaFile, MediaType, Task, TaskEvent, TaskPriority, TaskStatus, TaskType};
+use mongodb::error::ErrorKind;
+use mongodb::options::InsertManyOptions;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+/// Longueur maximale d'une clé d'idempotence
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Nombre maximal de tâches par requête `POST /tasks/batch`
+pub(crate) const MAX_BATCH_SIZE: usize = 1000;
+
+/// Nombre maximal de dépendances d'une tâche
+pub(crate) const MAX_DEPENDENCIES: usize = 32;
+
+/// Timeout maximal d'une tentative (24 h)
+const MAX_TIMEOUT_SECONDS: u64 = 86_400;
+
+/// Résultat d'une création de tâche
+pub struct CreatedTask {
+    pub task_id: String,
+    /// Vrai si la clé d'idempotence désignait une tâche déjà créée
+    pub replayed: bool,
+}
+
 pub async fn create_task(
     state: &AppState,
     dto: CreateTaskDto,
-) -> Result<String, ApiError> {
+    idempotency_key: Option<String>,
+) -> Result<CreatedTask, ApiError> {
+    // 1-4. Valider et construire la tâche
+    let mut task = build_task(dto, idempotency_key)?;
+    let task_id = task.id.clone();
+    if let Some(ref job_id) = task.job_id {
+        ensure_job_open(state, job_id).await?;
+    }
+    let missing = missing_dependencies(state, task.depends_on.iter().map(String::as_str).collect()).await?;
+    if let Some(dependency) = missing.into_iter().next() {
+        return Err(ApiError::InvalidInput(format!("Dependency not found: {}", dependency)));
+    }
+    
+    // 5. Sauvegarder MongoDB avec son marqueur d'outbox, dans le même
+    // document : si l'enqueue échoue, le relais des workers l'enfilera
+    task.outbox = Some(OutboxEntry::new());
+    let collection = state.get_database().collection::<Task>("tasks");
+    if let Err(e) = collection.insert_one(&task, None).await {
+        return match (is_duplicate_key_error(&e), &task.idempotency_key) {
+            (true, Some(key)) => replay_idempotent_create(state, key, &task.request_fingerprint).await,
+            _ => Err(e.into()),
+        };
+    }
+    
+    tracing::info!(task_id = %task_id, "Task saved to MongoDB");
+    
+    // 6. Enqueue Redis dans la lane de sa priorité, ou en attente si différée
+    match enqueue_created(state, &task).await {
+        Ok(()) => clear_outbox(state, vec![task_id.clone()]).await,
+        Err(e) => {
+            tracing::warn!(task_id = %task_id, error = %e, "Task enqueue failed, left to the outbox relay");
+        }
+    }
+    publish_event(state, TaskEvent::created(&task)).await;
+    
+    // 7. Incrémenter métrique
+    state.metrics.increment_created();
+    
+    Ok(CreatedTask { task_id, replayed: false })
+}
+
+/// Enfile une tâche tout juste insérée ; une tâche bloquée ne l'est que si
+/// ses dépendances se sont terminées avant l'insertion
+async fn enqueue_created(state: &AppState, task: &Task) -> Result<(), ApiError> {
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    if task.status == TaskStatus::Blocked {
+        let outcome = release_if_ready(&state.get_database(), &mut conn, &task.id).await?;
+        tracing::info!(task_id = %task.id, outcome = ?outcome, "Task blocked on its dependencies");
+    } else {
+        let mut pipe = redis::pipe();
+        let queue_name = enqueue_task(pipe.atomic(), task);
+        let _: () = pipe.query_async(&mut conn).await?;
+        
+        tracing::info!(task_id = %task.id, queue = %queue_name, "Task enqueued");
+    }
+    
+    Ok(())
+}
+
+/// Retire le marqueur d'outbox de tâches enfilées. Un échec est seulement
+/// journalisé : le relais ne ré-enfile pas une tâche déjà en queue.
+async fn clear_outbox(state: &AppState, task_ids: Vec<String>) {
+    if let Err(e) = outbox::clear(&state.get_database(), &task_ids).await {
+        tracing::warn!(count = task_ids.len(), error = %e, "Failed to clear outbox markers");
+    }
+}
+
+/// Crée plusieurs tâches en une requête : un seul `insert_many` non ordonné
+/// et un seul pipeline Redis. Chaque élément a son propre résultat, un
+/// élément invalide n'empêche pas la création des autres.
+pub async fn create_tasks_batch(
+    state: &AppState,
+    dtos: Vec<CreateTaskDto>,
+) -> Result<Vec<BatchItemResult>, ApiError> {
+    if dtos.is_empty() {
+        return Err(ApiError::InvalidInput("Batch cannot be empty".to_string()));
+    }
+    if dtos.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::InvalidInput(
+            format!("Batch cannot exceed {} tasks", MAX_BATCH_SIZE)
+        ));
+    }
+    
+    let mut results: Vec<BatchItemResult> = Vec::with_capacity(dtos.len());
+    let mut tasks = Vec::new();
+    
+    // 1. Valider et construire chaque tâche (index de la tâche dans le lot)
+    for (index, dto) in dtos.into_iter().enumerate() {
+        match build_task(dto, None) {
+            Ok(task) => {
+                results.push(BatchItemResult::created(index, task.id.clone()));
+                tasks.push((index, task));
+            }
+            Err(e) => results.push(BatchItemResult::failed(index, e.to_string())),
+        }
+    }
+    
+    // Les tâches rattachées à un job inconnu ou annulé sont refusées
+    let job_ids: HashSet<&str> = tasks.iter().filter_map(|(_, task)| task.job_id.as_deref()).collect();
+    if !job_ids.is_empty() {
+        let open = open_job_ids(state, job_ids).await?;
+        tasks.retain(|(index, task)| match task.job_id {
+            Some(ref job_id) if !open.contains(job_id) => {
+                results[*index] = BatchItemResult::failed(*index, format!("Job not found or cancelled: {}", job_id));
+                false
+            }
+            _ => true,
+        });
+    }
+    
+    // Les dépendances doivent exister
+    let dependencies: HashSet<&str> = tasks
+        .iter()
+        .flat_map(|(_, task)| task.depends_on.iter().map(String::as_str))
+        .collect();
+    if !dependencies.is_empty() {
+        let missing = missing_dependencies(state, dependencies).await?;
+        tasks.retain(|(index, task)| match task.depends_on.iter().find(|id| missing.contains(*id)) {
+            Some(dependency) => {
+                results[*index] = BatchItemResult::failed(*index, format!("Dependency not found: {}", dependency));
+                false
+            }
+            None => true,
+        });
+    }
+    
+    if tasks.is_empty() {
+        return Ok(results);
+    }
+    
+    // 2. Insertion groupée, non ordonnée, chaque tâche avec son marqueur
+    // d'outbox : les échecs sont rapportés par position
+    for (_, task) in tasks.iter_mut() {
+        task.outbox = Some(OutboxEntry::new());
+    }
+    
+    let collection = state.get_database().collection::<Task>("tasks");
+    let options = InsertManyOptions::builder().ordered(false).build();
+    let write_errors = match collection
+        .insert_many(tasks.iter().map(|(_, task)| task), options)
+        .await
+    {
+        Ok(_) => Vec::new(),
+        Err(e) => match e.kind.as_ref() {
+            ErrorKind::BulkWrite(failure) => failure.write_errors.clone().unwrap_or_default(),
+            _ => return Err(e.into()),
+        },
+    };
+    
+    let mut rejected = HashSet::new();
+    for write_error in write_errors {
+        let (index, task) = &tasks[write_error.index];
+        rejected.insert(write_error.index);
+        
+        results[*index] = match (write_error.code, &task.idempotency_key) {
+            // Clé déjà utilisée (par une requête précédente ou plus tôt dans ce lot)
+            (11000, Some(key)) => match replay_idempotent_create(state, key, &task.request_fingerprint).await {
+                Ok(created) => BatchItemResult::replayed(*index, created.task_id),
+                Err(e) => BatchItemResult::failed(*index, e.to_string()),
+            },
+            _ => BatchItemResult::failed(*index, write_error.message),
+        };
+    }
+    
+    let inserted: Vec<&Task> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(position, _)| !rejected.contains(position))
+        .map(|(_, (_, task))| task)
+        .collect();
+    
+    if inserted.is_empty() {
+        return Ok(results);
+    }
+    
+    // 3. Tous les enqueues dans un seul pipeline ; les tâches bloquées
+    // attendent leurs dépendances. En cas d'échec, le relais prend le relais.
+    match enqueue_batch(state, &inserted).await {
+        Ok(()) => clear_outbox(state, inserted.iter().map(|task| task.id.clone()).collect()).await,
+        Err(e) => {
+            tracing::warn!(count = inserted.len(), error = %e, "Batch enqueue failed, left to the outbox relay");
+        }
+    }
+    publish_events(state, inserted.iter().map(|task| TaskEvent::created(task)).collect()).await;
+    
+    tracing::info!(
+        created = inserted.len(),
+        failed = results.iter().filter(|result| result.error.is_some()).count(),
+        "Task batch enqueued"
+    );
+    
+    state.metrics.add_created(inserted.len() as u64);
+    
+    Ok(results)
+}
+
+/// Enfile les tâches d'un lot tout juste insérées
+async fn enqueue_batch(state: &AppState, inserted: &[&Task]) -> Result<(), ApiError> {
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    let (blocked, ready): (Vec<&Task>, Vec<&Task>) = inserted
+        .iter()
+        .partition(|task| task.status == TaskStatus::Blocked);
+    
+    if !ready.is_empty() {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for task in ready {
+            enqueue_task(&mut pipe, task);
+        }
+        let _: () = pipe.query_async(&mut conn).await?;
+    }
+    
+    let db = state.get_database();
+    for task in blocked {
+        release_if_ready(&db, &mut conn, &task.id).await?;
+    }
+    
+    Ok(())
+}
+
+/// Valide un DTO et construit la tâche correspondante, prête à être insérée
+fn build_task(
+    mut dto: CreateTaskDto,
+    idempotency_key: Option<String>,
+) -> Result<Task, ApiError> {
     // 1. Valider
     validate_task_dto(&dto)?;
+    let idempotency_key = resolve_idempotency_key(idempotency_key, dto.idempotency_key.take())?;
+    let fingerprint = idempotency_key.as_ref().map(|_| request_fingerprint(&dto)).transpose()?;
     
-    // 2. Convertir task_type
+    // 2. Convertir task_type et priorité
     let task_type = parse_task_type(&dto.task_type)?;
+    let priority = parse_priority(dto.priority.as_deref())?;
+    let run_at = parse_run_at(&dto)?;
+    let expires_at = parse_expires_at(&dto, run_at)?;
     
     // 3. Créer MediaFile
     let mut metadata = HashMap::new();
@@ -30,33 +290,98 @@ pub async fn create_task(
     };
     
     // 4. Créer Task
-    let task = Task::new(task_type.clone(), media);
-    let task_id = task.id.clone();
-    
-    // 5. Sauvegarder MongoDB
-    let db = state.get_database();
-    let collection = db.collection::<Task>("tasks");
-    collection.insert_one(&task, None).await?;
+    let mut task = Task::new(task_type, media).with_priority(priority);
+    if dto.depends_on.is_empty() {
+        if let Some(run_at) = run_at {
+            task = task.with_run_at(run_at);
+        }
+    } else {
+        // Le run_at s'appliquera au déblocage
+        task.run_at = run_at;
+        task.depends_on = dto.depends_on;
+        task.input_from = dto.input_from;
+        task.on_dependency_failure = parse_dependency_failure_policy(dto.on_dependency_failure.as_deref())?;
+        task.update_status(TaskStatus::Blocked)?;
+    }
+    if task.status == TaskStatus::Pending {
+        task.update_status(TaskStatus::Queued)?;
+    }
+    task.timeout_seconds = dto.timeout_seconds;
+    task.expires_at = expires_at;
+    task.job_id = dto.job_id;
+    task.idempotency_key = idempotency_key;
+    task.request_fingerprint = fingerprint;
     
-    tracing::info!(task_id = %task_id, "Task saved to MongoDB");
+    Ok(task)
+}
+
+/// Ajoute au pipeline l'enqueue d'une tâche dans la lane de sa priorité, ou
+/// en attente si elle est différée ; renvoie la queue visée
+fn enqueue_task(pipe: &mut redis::Pipeline, task: &Task) -> String {
+    match (&task.status, task.run_at) {
+        (TaskStatus::Scheduled, Some(run_at)) => {
+            queue::schedule_task(pipe, task, run_at.timestamp_millis());
+            queue::scheduled_key(&task.task_type)
+        }
+        _ => {
+            queue::push_task(pipe, task);
+            queue::lane_key(&task.task_type, task.priority)
+        }
+    }
+}
+
+/// Clé d'idempotence de la requête : en-tête ou champ du DTO, pas deux valeurs différentes
+fn resolve_idempotency_key(
+    header: Option<String>,
+    field: Option<String>,
+) -> Result<Option<String>, ApiError> {
+    let key = match (header, field) {
+        (Some(header), Some(field)) if header != field => {
+            return Err(ApiError::InvalidInput(
+                "Idempotency-Key header and idempotency_key field differ".to_string()
+            ));
+        }
+        (header, field) => header.or(field),
+    };
     
-    // 6. Enqueue Redis
-    let mut conn = state.redis_client.get_async_connection().await?;
-    let queue_name = format!("queue:{}", task_type);
-    let serialized = serde_json::to_string(&task)?;
+    if let Some(ref key) = key {
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            return Err(ApiError::InvalidInput(
+                format!("Idempotency key must be 1-{} characters", MAX_IDEMPOTENCY_KEY_LEN)
+            ));
+        }
+    }
     
-    let _: () = redis::cmd("LPUSH")
-        .arg(&queue_name)
-        .arg(serialized)
-        .query_async(&mut conn)
-        .await?;
+    Ok(key)
+}
+
+/// Empreinte du corps de la requête (sans la clé d'idempotence)
+fn request_fingerprint(dto: &CreateTaskDto) -> Result<String, ApiError> {
+    let body = serde_json::to_vec(dto)?;
+    Ok(format!("{:x}", Sha256::digest(body)))
+}
+
+/// Renvoie la tâche déjà créée avec cette clé, si la requête est bien la même
+async fn replay_idempotent_create(
+    state: &AppState,
+    key: &str,
+    fingerprint: &Option<String>,
+) -> Result<CreatedTask, ApiError> {
+    let collection = state.get_database().collection::<Task>("tasks");
+    let existing = collection
+        .find_one(mongodb::bson::doc! { "idempotency_key": key }, None)
+        .await?
+        .ok_or_else(|| ApiError::InternalError(format!("Idempotency key {} has no task", key)))?;
     
-    tracing::info!(task_id = %task_id, queue = %queue_name, "Task enqueued");
+    if existing.request_fingerprint != *fingerprint {
+        return Err(ApiError::Conflict(
+            format!("Idempotency key {} was already used with a different request", key)
+        ));
+    }
     
-    // 7. Incrémenter métrique
-    state.metrics.increment_created();
+    tracing::info!(task_id = %existing.id, idempotency_key = %key, "Idempotent create replayed");
     
-    Ok(task_id)
+    Ok(CreatedTask { task_id: existing.id, replayed: true })
 }
 
 pub async fn get_task(
@@ -75,9 +400,44 @@ pub async fn get_task(
     Ok(task_to_response(task))
 }
 
+pub async fn get_task_history(
+    state: &AppState,
+    task_id: &str,
+) -> Result<TaskHistoryResponse, ApiError> {
+    let db = state.get_database();
+    let collection = db.collection::<Task>("tasks");
+    
+    let filter = mongodb::bson::doc! { "task_id": task_id };
+    let task = collection
+        .find_one(filter, None)
+        .await?
+        .ok_or_else(|| ApiError::TaskNotFound(task_id.to_string()))?;
+    
+    let transitions = task
+        .history
+        .into_iter()
+        .map(|transition| TransitionResponse {
+            from: transition.from.to_string(),
+            to: transition.to.to_string(),
+            at: transition.at.to_rfc3339(),
+            worker_id: transition.worker_id,
+            attempt: transition.attempt,
+            error: transition.error,
+            reason: transition.reason,
+        })
+        .collect();
+    
+    Ok(TaskHistoryResponse {
+        task_id: task.id,
+        status: task.status.to_string(),
+        transitions,
+    })
+}
+
 pub async fn list_tasks(
     state: &AppState,
     status_filter: Option<String>,
+    job_filter: Option<String>,
     limit: i64,
     skip: u64,
 ) -> Result<Vec<TaskResponse>, ApiError> {
@@ -88,6 +448,9 @@ pub async fn list_tasks(
     if let Some(status) = status_filter {
         filter.insert("status", status);
     }
+    if let Some(job_id) = job_filter {
+        filter.insert("job_id", job_id);
+    }
     
     let options = mongodb::options::FindOptions::builder()
         .limit(limit)
@@ -121,51 +484,29 @@ pub async fn cancel_task(
         .ok_or_else(|| ApiError::TaskNotFound(task_id.to_string()))?;
     
     // Vérifier que la tâche peut être annulée
-    if task.status == TaskStatus::Completed || task.status == TaskStatus::Failed {
+    if !task.status.can_transition_to(&TaskStatus::Cancelling) {
         return Err(ApiError::InvalidInput(
             format!("Cannot cancel task with status: {}", task.status)
         ));
     }
     
-    // 2. Publier message de cancellation sur Redis pub/sub
-    let channel = format!("task:cancel:{}", task_id);
-    let mut conn = state.redis_client.get_async_connection().await?;
-    
-    let _: i32 = redis::cmd("PUBLISH")
-        .arg(&channel)
-        .arg("cancel")
-        .query_async(&mut conn)
-        .await?;
+    // 2. Statut d'abord (Cancelling) : un worker qui la sort de la queue
+    // maintenant ne la traitera pas
+    let mut cancelling = task.clone();
+    cancelling.update_status(TaskStatus::Cancelling)?;
+    persist_status(state, &mut cancelling).await?;
     
-    tracing::info!(
-        task_id = %task_id,
-        channel = %channel,
-        "Published cancellation message to Redis"
-    );
-    
-    // 3. Mettre à jour le statut dans MongoDB (Cancelling d'abord)
-    let update = mongodb::bson::doc! {
-        "$set": {
-            "status": "Cancelling",
-            "updated_at": mongodb::bson::DateTime::from_millis(chrono::Utc::now().timestamp_millis())
-        }
-    };
-    
-    collection.update_one(filter, update, None).await?;
-    
-    // 4. Nettoyer Redis queue (si la tâche n'a pas encore été prise)
+    // 3. Retirer la tâche si elle n'a pas encore été prise, sinon prévenir son worker
     let removed = remove_task_from_redis_queue(state, &task).await?;
     
     if removed {
         // La tâche était encore en queue, on peut la marquer directement comme Cancelled
-        let filter = mongodb::bson::doc! { "task_id": task_id };
-        let update = mongodb::bson::doc! {
-            "$set": {
-                "status": "Cancelled",
-                "updated_at": mongodb::bson::DateTime::from_millis(chrono::Utc::now().timestamp_millis())
-            }
-        };
-        collection.update_one(filter, update, None).await?;
+        cancelling.update_status(TaskStatus::Cancelled)?;
+        persist_status(state, &mut cancelling).await?;
+        
+        let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+        settle_dependents(&db, &mut conn, task_id).await?;
+        publish_event(state, TaskEvent::cancelled(task_id)).await;
         
         tracing::info!(
             task_id = %task_id,
@@ -178,53 +519,241 @@ pub async fn cancel_task(
         );
     }
     
-    // 5. Incrémenter métrique
+    // 4. Incrémenter métrique
     state.metrics.increment_cancelled();
     
     Ok(())
 }
 
-/// Retire une tâche de la queue Redis
-async fn remove_task_from_redis_queue(
+pub async fn pause_task(
+    state: &AppState,
+    task_id: &str,
+) -> Result<TaskStatus, ApiError> {
+    let db = state.get_database();
+    let collection = db.collection::<Task>("tasks");
+    
+    // 1. Récupérer la tâche
+    let filter = mongodb::bson::doc! { "task_id": task_id };
+    let task = collection
+        .find_one(filter.clone(), None)
+        .await?
+        .ok_or_else(|| ApiError::TaskNotFound(task_id.to_string()))?;
+    
+    if task.status == TaskStatus::Paused || !task.status.can_transition_to(&TaskStatus::Paused) {
+        return Err(ApiError::InvalidInput(
+            format!("Cannot pause task with status: {}", task.status)
+        ));
+    }
+    
+    // 2. Statut d'abord : un worker qui la sort de la queue maintenant la retiendra
+    let mut paused = task.clone();
+    paused.update_status(TaskStatus::Paused)?;
+    persist_status(state, &mut paused).await?;
+    
+    // 3. Retenir la tâche hors des queues si elle n'a pas encore été prise
+    let held_back = task.status != TaskStatus::Processing && hold_task_back(state, &task).await?;
+    
+    if held_back {
+        tracing::info!(task_id = %task_id, "Task paused and held back from queue");
+    } else if task.status != TaskStatus::Processing {
+        // Dans aucune queue (enqueue pas encore relayé, entrée perdue) ou en
+        // train d'être retenue par le worker qui vient de la sortir : la
+        // reprise la remettra en queue si personne ne la détient
+        tracing::info!(task_id = %task_id, status = %task.status, "Task paused outside of any queue");
+    } else {
+        // En cours de traitement : le worker suspend le processor
+        state.pubsub
+            .pause_task(task_id)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("Failed to publish pause command: {}", e)))?;
+        
+        tracing::info!(task_id = %task_id, "Pause command sent to worker");
+    }
+    
+    Ok(TaskStatus::Paused)
+}
+
+pub async fn resume_task(
+    state: &AppState,
+    task_id: &str,
+) -> Result<TaskStatus, ApiError> {
+    let db = state.get_database();
+    let collection = db.collection::<Task>("tasks");
+    
+    let filter = mongodb::bson::doc! { "task_id": task_id };
+    let mut task = collection
+        .find_one(filter, None)
+        .await?
+        .ok_or_else(|| ApiError::TaskNotFound(task_id.to_string()))?;
+    
+    if task.status != TaskStatus::Paused {
+        return Err(ApiError::InvalidInput(
+            format!("Cannot resume task with status: {}", task.status)
+        ));
+    }
+    
+    let status = match release_held_task(state, &task).await? {
+        Some(status) => {
+            tracing::info!(task_id = %task_id, status = %status, "Paused task released back to queue");
+            status
+        }
+        None if worker_holds_task(state, &task).await? => {
+            // Pas retenue : elle est suspendue chez un worker
+            state.pubsub
+                .resume_task(task_id)
+                .await
+                .map_err(|e| ApiError::InternalError(format!("Failed to publish resume command: {}", e)))?;
+            
+            tracing::info!(task_id = %task_id, "Resume command sent to worker");
+            TaskStatus::Processing
+        }
+        None => {
+            // Ni retenue ni détenue : pausée avant d'atteindre sa queue
+            let status = task.resumed_status(Utc::now());
+            let mut waiting = task.clone();
+            waiting.update_status(status.clone())?;
+            let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+            outbox::enqueue_if_absent(&mut conn, &waiting).await?;
+            
+            tracing::info!(task_id = %task_id, status = %status, "Paused task was in no queue, enqueued on resume");
+            status
+        }
+    };
+    
+    task.update_status(status.clone())?;
+    persist_status(state, &mut task).await?;
+    if status == TaskStatus::Queued {
+        publish_event(state, TaskEvent::queued(task_id)).await;
+    }
+    
+    Ok(status)
+}
+
+/// Écrit le statut d'une tâche dans MongoDB, à condition que le statut stocké
+/// puisse encore y mener (sinon la tâche a changé entre-temps)
+async fn persist_status(
+    state: &AppState,
+    task: &mut Task,
+) -> Result<(), ApiError> {
+    let collection = state.get_database().collection::<Task>("tasks");
+    let update = status_update(task)
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    
+    let result = collection
+        .update_one(transition_filter(&task.id, &task.status), update, None)
+        .await?;
+    
+    if result.matched_count == 0 {
+        return Err(ApiError::Conflict(
+            format!("Task {} changed status concurrently", task.id)
+        ));
+    }
+    task.mark_transitions_saved();
+    
+    Ok(())
+}
+
+/// Déplace une tâche en attente (lane ou sorted set des différées) vers le hash des pauses
+async fn hold_task_back(
     state: &AppState,
     task: &Task,
 ) -> Result<bool, ApiError> {
-    let mut conn = state.redis_client.get_async_connection().await?;
-    let queue_name = format!("queue:{}", task.task_type);
-    
-    // Récupérer toutes les tâches de la queue
-    let tasks: Vec<String> = redis::cmd("LRANGE")
-        .arg(&queue_name)
-        .arg(0)
-        .arg(-1)
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    let paused_key = queue::paused_key(&task.task_type);
+    
+    // Une tâche différée peut avoir été promue entre-temps : on cherche aussi dans la lane
+    let mut sources = Vec::new();
+    if task.status == TaskStatus::Scheduled || task.next_retry_at.is_some() {
+        sources.push((queue::scheduled_key(&task.task_type), "zset"));
+    }
+    sources.push((queue::lane_key(&task.task_type, task.priority), "list"));
+    
+    for (source, kind) in sources {
+        let moved: i32 = queue::pause_script()
+            .key(&source)
+            .key(&paused_key)
+            .arg(&task.id)
+            .arg(kind)
+            .invoke_async(&mut conn)
+            .await?;
+        
+        if moved > 0 {
+            return Ok(true);
+        }
+    }
+    
+    Ok(false)
+}
+
+/// Remet en queue une tâche retenue par une pause ; renvoie son nouveau statut,
+/// ou `None` si elle n'était pas retenue
+async fn release_held_task(
+    state: &AppState,
+    task: &Task,
+) -> Result<Option<TaskStatus>, ApiError> {
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    // Une tâche retenue avant son échéance retourne dans les différées
+    let due_at = task
+        .next_retry_at
+        .or(task.run_at)
+        .filter(|at| *at > Utc::now());
+    
+    let released: i32 = queue::resume_script()
+        .key(queue::paused_key(&task.task_type))
+        .key(queue::lane_key(&task.task_type, task.priority))
+        .key(queue::notify_key(&task.task_type))
+        .key(queue::scheduled_key(&task.task_type))
+        .arg(&task.id)
+        .arg(due_at.map(|at| at.timestamp_millis().to_string()).unwrap_or_default())
+        .invoke_async(&mut conn)
+        .await?;
+    
+    if released == 0 {
+        return Ok(None);
+    }
+    
+    Ok(Some(task.resumed_status(Utc::now())))
+}
+
+/// Vrai si un worker détient encore la tâche (attribuée et sous lease valide)
+async fn worker_holds_task(
+    state: &AppState,
+    task: &Task,
+) -> Result<bool, ApiError> {
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    let lease_deadline: Option<f64> = redis::cmd("ZSCORE")
+        .arg(queue::LEASES_KEY)
+        .arg(&task.id)
         .query_async(&mut conn)
         .await?;
     
-    // Chercher la tâche à retirer
-    for task_json in tasks.iter() {
-        if let Ok(t) = serde_json::from_str::<Task>(task_json) {
-            if t.id == task.id {
-                // Retirer cette occurrence (LREM)
-                let count: i32 = redis::cmd("LREM")
-                    .arg(&queue_name)
-                    .arg(1) // Retirer 1 occurrence
-                    .arg(task_json)
-                    .query_async(&mut conn)
-                    .await?;
-                
-                tracing::debug!(
-                    task_id = %task.id,
-                    queue = %queue_name,
-                    removed_count = count,
-                    "Task removed from Redis queue"
-                );
-                
-                return Ok(count > 0);
-            }
-        }
+    Ok(task.is_held_by_worker(lease_deadline.map(|deadline| deadline as i64), Utc::now()))
+}
+
+/// Retire atomiquement une tâche de l'endroit où elle attend (lane, différées
+/// ou pauses) ; si un worker l'a déjà prise, lui publie l'annulation
+async fn remove_task_from_redis_queue(
+    state: &AppState,
+    task: &Task,
+) -> Result<bool, ApiError> {
+    // Une tâche bloquée n'est dans aucune queue
+    if task.status == TaskStatus::Blocked {
+        return Ok(true);
     }
     
-    Ok(false) // Tâche non trouvée dans Redis
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    let removed: i32 = queue::cancel_script()
+        .key(queue::lane_key(&task.task_type, task.priority))
+        .key(queue::notify_key(&task.task_type))
+        .key(queue::scheduled_key(&task.task_type))
+        .key(queue::paused_key(&task.task_type))
+        .arg(&task.id)
+        .arg(cancel_channel(&task.id))
+        .invoke_async(&mut conn)
+        .await?;
+    
+    Ok(removed > 0)
 }
 
 fn validate_task_dto(dto: &CreateTaskDto) -> Result<(), ApiError> {
@@ -234,10 +763,51 @@ fn validate_task_dto(dto: &CreateTaskDto) -> Result<(), ApiError> {
         ));
     }
     
-    if dto.file_path.is_empty() {
+    if let Some(ref priority) = dto.priority {
+        if !["high", "normal", "low"].contains(&priority.as_str()) {
+            return Err(ApiError::InvalidInput(
+                format!("Invalid priority: {}. Must be 'high', 'normal', or 'low'", priority)
+            ));
+        }
+    }
+    
+    if dto.run_at.is_some() && dto.delay_seconds.is_some() {
+        return Err(ApiError::InvalidInput(
+            "run_at and delay_seconds are mutually exclusive".to_string()
+        ));
+    }
+    
+    // Le fichier d'entrée peut venir d'une dépendance
+    if dto.file_path.is_empty() && dto.input_from.is_none() {
         return Err(ApiError::InvalidInput("file_path cannot be empty".to_string()));
     }
     
+    if dto.depends_on.len() > MAX_DEPENDENCIES {
+        return Err(ApiError::InvalidInput(
+            format!("A task cannot depend on more than {} tasks", MAX_DEPENDENCIES)
+        ));
+    }
+    
+    if dto.depends_on.iter().collect::<HashSet<_>>().len() != dto.depends_on.len() {
+        return Err(ApiError::InvalidInput("depends_on contains duplicates".to_string()));
+    }
+    
+    if let Some(ref input_from) = dto.input_from {
+        if !dto.depends_on.contains(input_from) {
+            return Err(ApiError::InvalidInput(
+                "input_from must be one of depends_on".to_string()
+            ));
+        }
+    }
+    
+    if dto.expires_at.is_some() && dto.expires_in_seconds.is_some() {
+        return Err(ApiError::InvalidInput(
+            "expires_at and expires_in_seconds are mutually exclusive".to_string()
+        ));
+    }
+    
+    validate_timeout(dto.timeout_seconds)?;
+    
     if dto.task_type == "image" {
         if let Some(quality) = dto.options.quality {
             if quality > 100 {
@@ -251,7 +821,16 @@ fn validate_task_dto(dto: &CreateTaskDto) -> Result<(), ApiError> {
     Ok(())
 }
 
-fn parse_task_type(task_type: &str) -> Result<TaskType, ApiError> {
+pub(crate) fn validate_timeout(timeout_seconds: Option<u64>) -> Result<(), ApiError> {
+    match timeout_seconds {
+        Some(timeout) if timeout == 0 || timeout > MAX_TIMEOUT_SECONDS => Err(ApiError::InvalidInput(
+            format!("timeout_seconds must be between 1 and {}", MAX_TIMEOUT_SECONDS)
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn parse_task_type(task_type: &str) -> Result<TaskType, ApiError> {
     match task_type {
         "video" => Ok(TaskType::VideoCompression),
         "audio" => Ok(TaskType::AudioProcessing),
@@ -260,6 +839,80 @@ fn parse_task_type(task_type: &str) -> Result<TaskType, ApiError> {
     }
 }
 
+fn parse_run_at(dto: &CreateTaskDto) -> Result<Option<DateTime<Utc>>, ApiError> {
+    if let Some(ref run_at) = dto.run_at {
+        let run_at = DateTime::parse_from_rfc3339(run_at)
+            .map_err(|e| ApiError::InvalidInput(format!("Invalid run_at: {}", e)))?;
+        return Ok(Some(run_at.with_timezone(&Utc)));
+    }
+    
+    Ok(dto
+        .delay_seconds
+        .map(|delay| Utc::now() + chrono::Duration::seconds(delay as i64)))
+}
+
+fn parse_expires_at(dto: &CreateTaskDto, run_at: Option<DateTime<Utc>>) -> Result<Option<DateTime<Utc>>, ApiError> {
+    let expires_at = match (&dto.expires_at, dto.expires_in_seconds) {
+        (Some(expires_at), _) => DateTime::parse_from_rfc3339(expires_at)
+            .map_err(|e| ApiError::InvalidInput(format!("Invalid expires_at: {}", e)))?
+            .with_timezone(&Utc),
+        (None, Some(ttl)) => Utc::now() + chrono::Duration::seconds(ttl as i64),
+        (None, None) => return Ok(None),
+    };
+    
+    if expires_at <= run_at.unwrap_or_else(Utc::now) {
+        return Err(ApiError::InvalidInput(
+            "expires_at must be later than now and than run_at".to_string()
+        ));
+    }
+    
+    Ok(Some(expires_at))
+}
+
+pub(crate) fn parse_priority(priority: Option<&str>) -> Result<TaskPriority, ApiError> {
+    match priority {
+        None | Some("normal") => Ok(TaskPriority::Normal),
+        Some("high") => Ok(TaskPriority::High),
+        Some("low") => Ok(TaskPriority::Low),
+        Some(other) => Err(ApiError::InvalidInput(format!("Invalid priority: {}", other))),
+    }
+}
+
+fn parse_dependency_failure_policy(policy: Option<&str>) -> Result<DependencyFailurePolicy, ApiError> {
+    match policy {
+        None | Some("cancel") => Ok(DependencyFailurePolicy::Cancel),
+        Some("fail") => Ok(DependencyFailurePolicy::Fail),
+        Some(other) => Err(ApiError::InvalidInput(
+            format!("Invalid on_dependency_failure: {}. Must be 'cancel' or 'fail'", other)
+        )),
+    }
+}
+
+/// Parmi `task_ids`, ceux qui ne désignent aucune tâche
+async fn missing_dependencies(
+    state: &AppState,
+    task_ids: HashSet<&str>,
+) -> Result<HashSet<String>, ApiError> {
+    if task_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+    
+    let collection = state.get_database().collection::<Task>("tasks");
+    let filter = mongodb::bson::doc! { "task_id": { "$in": task_ids.iter().collect::<Vec<_>>() } };
+    
+    let mut found = HashSet::new();
+    let mut cursor = collection.find(filter, None).await?;
+    while cursor.advance().await? {
+        found.insert(cursor.deserialize_current()?.id);
+    }
+    
+    Ok(task_ids
+        .into_iter()
+        .filter(|id| !found.contains(*id))
+        .map(str::to_string)
+        .collect())
+}
+
 fn task_type_to_media_type(task_type: &TaskType) -> MediaType {
     match task_type {
         TaskType::VideoCompression => MediaType::Video,
@@ -268,7 +921,7 @@ fn task_type_to_media_type(task_type: &TaskType) -> MediaType {
     }
 }
 
-fn add_options_to_metadata(metadata: &mut HashMap<String, String>, options: &TaskOptionsDto) {
+pub(crate) fn add_options_to_metadata(metadata: &mut HashMap<String, String>, options: &TaskOptionsDto) {
     if let Some(ref codec) = options.video_codec {
         metadata.insert("video_codec".to_string(), codec.clone());
     }
@@ -298,14 +951,24 @@ fn add_options_to_metadata(metadata: &mut HashMap<String, String>, options: &Tas
     }
 }
 
-fn task_to_response(task: Task) -> TaskResponse {
+pub(crate) fn task_to_response(task: Task) -> TaskResponse {
+    let timeout = task.timeout();
+    
     TaskResponse {
         id: task.id,
         task_type: task.task_type.to_string(),
         status: task.status.to_string(),
+        priority: task.priority.to_string(),
         progress: task.progress,
         error: task.error,
         output_path: task.output_path,
+        run_at: task.run_at.map(|run_at| run_at.to_rfc3339()),
+        retry_count: task.retry_count,
+        next_retry_at: task.next_retry_at.map(|retry_at| retry_at.to_rfc3339()),
+        timeout_seconds: timeout.as_secs(),
+        expires_at: task.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+        depends_on: task.depends_on,
+        job_id: task.job_id,
         created_at: task.created_at.to_rfc3339(),
         updated_at: task.updated_at.to_rfc3339(),
     }
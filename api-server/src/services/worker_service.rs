@@ -0,0 +1,41 @@
+use crate::dtos::WorkerResponse;
+use crate::error::ApiError;
+use crate::state::AppState;
+use shared::{queue, WorkerInfo};
+use std::collections::HashMap;
+
+pub async fn list_workers(state: &AppState) -> Result<Vec<WorkerResponse>, ApiError> {
+    let mut conn = state.redis_client.get_multiplexed_async_connection().await?;
+    
+    let entries: HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(queue::WORKERS_KEY)
+        .query_async(&mut conn)
+        .await?;
+    
+    let mut workers: Vec<WorkerInfo> = entries
+        .values()
+        .filter_map(|json| serde_json::from_str(json).ok())
+        .collect();
+    
+    workers.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+    
+    Ok(workers.into_iter().map(worker_to_response).collect())
+}
+
+fn worker_to_response(worker: WorkerInfo) -> WorkerResponse {
+    let alive = worker.is_alive();
+    
+    WorkerResponse {
+        worker_id: worker.worker_id,
+        task_types: worker.task_types.iter().map(|task_type| task_type.to_string()).collect(),
+        hostname: worker.hostname,
+        version: worker.version,
+        started_at: worker.started_at.to_rfc3339(),
+        last_heartbeat: worker.last_heartbeat.to_rfc3339(),
+        concurrency: worker.concurrency,
+        current_task_ids: worker.current_task_ids,
+        processed_count: worker.processed_count,
+        failed_count: worker.failed_count,
+        alive,
+    }
+}
@@ -0,0 +1,99 @@
+use crate::dtos::{CreateTaskDto, WorkflowRunDto, WorkflowRunResponse, WorkflowStepTask};
+use crate::error::ApiError;
+use crate::services::job_service::cancel_job;
+use crate::services::task_service::{create_task, parse_priority};
+use crate::state::AppState;
+use crate::workflows::WorkflowTemplate;
+use shared::Job;
+use std::collections::HashMap;
+
+/// Modèles chargés au démarrage, triés par nom
+pub fn list_workflows(state: &AppState) -> Vec<WorkflowTemplate> {
+    let mut workflows: Vec<WorkflowTemplate> = state.workflows.values().cloned().collect();
+    workflows.sort_by(|a, b| a.name.cmp(&b.name));
+    workflows
+}
+
+pub fn get_workflow(state: &AppState, name: &str) -> Result<WorkflowTemplate, ApiError> {
+    state
+        .workflows
+        .get(name)
+        .cloned()
+        .ok_or_else(|| ApiError::WorkflowNotFound(name.to_string()))
+}
+
+/// Développe un workflow en tâches rattachées à un nouveau job : chaque étape
+/// devient une tâche qui dépend des tâches de ses étapes précédentes
+pub async fn run_workflow(
+    state: &AppState,
+    name: &str,
+    dto: WorkflowRunDto,
+) -> Result<WorkflowRunResponse, ApiError> {
+    let workflow = get_workflow(state, name)?;
+    
+    if dto.file_path.is_empty() {
+        return Err(ApiError::InvalidInput("file_path cannot be empty".to_string()));
+    }
+    parse_priority(dto.priority.as_deref())?;
+    
+    let job = Job::new(Some(dto.name.clone().unwrap_or_else(|| format!("workflow:{}", workflow.name))));
+    let job_id = job.id.clone();
+    state.get_database().collection::<Job>("jobs").insert_one(&job, None).await?;
+    
+    // Les étapes ne référencent que des étapes précédentes : leurs tâches existent déjà
+    let mut task_ids: HashMap<&str, String> = HashMap::new();
+    let mut tasks = Vec::with_capacity(workflow.steps.len());
+    
+    for step in &workflow.steps {
+        let depends_on = step
+            .dependencies()
+            .into_iter()
+            .map(|dependency| task_ids[dependency].clone())
+            .collect();
+        let input_from = step.input_from.as_deref().map(|input_from| task_ids[input_from].clone());
+        
+        let task_dto = CreateTaskDto {
+            task_type: step.task_type.clone(),
+            // Le fichier d'une étape alimentée par une autre est fixé à son déblocage
+            file_path: if input_from.is_some() { String::new() } else { dto.file_path.clone() },
+            file_size: if input_from.is_some() { 0 } else { dto.file_size },
+            original_name: dto.original_name.clone(),
+            mime_type: dto.mime_type.clone(),
+            options: step.options.clone(),
+            priority: dto.priority.clone().or_else(|| step.priority.clone()),
+            run_at: None,
+            delay_seconds: None,
+            timeout_seconds: step.timeout_seconds,
+            expires_at: None,
+            expires_in_seconds: None,
+            depends_on,
+            input_from,
+            on_dependency_failure: step.on_dependency_failure.clone(),
+            job_id: Some(job_id.clone()),
+            idempotency_key: None,
+        };
+        
+        match create_task(state, task_dto, None).await {
+            Ok(created) => {
+                task_ids.insert(&step.id, created.task_id.clone());
+                tasks.push(WorkflowStepTask { step: step.id.clone(), task_id: created.task_id });
+            }
+            Err(e) => {
+                // Ne pas laisser s'exécuter un workflow incomplet
+                tracing::error!(workflow = %workflow.name, job_id = %job_id, step = %step.id, error = %e, "Workflow expansion failed");
+                if let Err(cancel_error) = cancel_job(state, &job_id).await {
+                    tracing::warn!(job_id = %job_id, error = %cancel_error, "Failed to cancel incomplete workflow run");
+                }
+                return Err(e);
+            }
+        }
+    }
+    
+    tracing::info!(workflow = %workflow.name, job_id = %job_id, tasks = tasks.len(), "Workflow run created");
+    
+    Ok(WorkflowRunResponse {
+        workflow: workflow.name,
+        job_id,
+        tasks,
+    })
+}
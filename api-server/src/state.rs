@@ -1,5 +1,8 @@
+use crate::workflows::WorkflowTemplate;
 use mongodb::Client as MongoClient;
 use redis::Client as RedisClient;
+use shared::PubSubClient;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
@@ -9,6 +12,8 @@ pub struct Metrics {
     pub tasks_completed: AtomicU64,
     pub tasks_failed: AtomicU64,
     pub tasks_cancelled: AtomicU64,
+    /// Expirées par les workers : compté depuis leurs événements `expired`
+    pub tasks_expired: AtomicU64,
 }
 
 impl Metrics {
@@ -18,6 +23,7 @@ impl Metrics {
             tasks_completed: AtomicU64::new(0),
             tasks_failed: AtomicU64::new(0),
             tasks_cancelled: AtomicU64::new(0),
+            tasks_expired: AtomicU64::new(0),
         }
     }
     
@@ -26,10 +32,16 @@ impl Metrics {
         self.tasks_created.fetch_add(1, Ordering::Relaxed);
     }
     
+    pub fn add_created(&self, count: u64) {
+        self.tasks_created.fetch_add(count, Ordering::Relaxed);
+    }
+    
+    #[allow(dead_code)]
     pub fn increment_completed(&self) {
         self.tasks_completed.fetch_add(1, Ordering::Relaxed);
     }
     
+    #[allow(dead_code)]
     pub fn increment_failed(&self) {
         self.tasks_failed.fetch_add(1, Ordering::Relaxed);
     }
@@ -38,6 +50,10 @@ impl Metrics {
         self.tasks_cancelled.fetch_add(1, Ordering::Relaxed);
     }
     
+    pub fn increment_expired(&self) {
+        self.tasks_expired.fetch_add(1, Ordering::Relaxed);
+    }
+    
     // Get methods
     pub fn get_created(&self) -> u64 {
         self.tasks_created.load(Ordering::Relaxed)
@@ -55,6 +71,10 @@ impl Metrics {
         self.tasks_cancelled.load(Ordering::Relaxed)
     }
     
+    pub fn get_expired(&self) -> u64 {
+        self.tasks_expired.load(Ordering::Relaxed)
+    }
+    
     // Set methods (pour sync avec MongoDB)
     pub fn set_created(&self, value: u64) {
         self.tasks_created.store(value, Ordering::Relaxed);
@@ -72,12 +92,17 @@ impl Metrics {
         self.tasks_cancelled.store(value, Ordering::Relaxed);
     }
     
+    pub fn set_expired(&self, value: u64) {
+        self.tasks_expired.store(value, Ordering::Relaxed);
+    }
+    
     // Reset all counters to zero
     pub fn reset_all(&self) {
         self.tasks_created.store(0, Ordering::Relaxed);
         self.tasks_completed.store(0, Ordering::Relaxed);
         self.tasks_failed.store(0, Ordering::Relaxed);
         self.tasks_cancelled.store(0, Ordering::Relaxed);
+        self.tasks_expired.store(0, Ordering::Relaxed);
     }
 }
 
@@ -86,8 +111,12 @@ impl Metrics {
 pub struct AppState {
     pub mongo_client: MongoClient,
     pub redis_client: RedisClient,
+    /// Publications (commandes, événements) sur une connexion partagée
+    pub pubsub: PubSubClient,
     pub database_name: String,
     pub metrics: Arc<Metrics>,
+    /// Modèles de workflow, par nom
+    pub workflows: HashMap<String, WorkflowTemplate>,
 }
 
 impl AppState {
@@ -95,6 +124,7 @@ impl AppState {
         mongo_uri: &str,
         redis_uri: &str,
         database_name: String,
+        workflows: HashMap<String, WorkflowTemplate>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         // Connexion MongoDB
         let mongo_client = MongoClient::with_uri_str(mongo_uri).await?;
@@ -111,7 +141,7 @@ impl AppState {
         let redis_client = RedisClient::open(redis_uri)?;
         
         // Test Redis
-        let mut conn = redis_client.get_async_connection().await?;
+        let mut conn = redis_client.get_multiplexed_async_connection().await?;
         let _: String = redis::cmd("PING")
             .query_async(&mut conn)
             .await?;
@@ -120,9 +150,11 @@ impl AppState {
         
         Ok(Self {
             mongo_client,
+            pubsub: PubSubClient::new(redis_client.clone()),
             redis_client,
             database_name,
             metrics: Arc::new(Metrics::new()),
+            workflows,
         })
     }
 
@@ -0,0 +1,179 @@
+use crate::dtos::TaskOptionsDto;
+use crate::services::task_service::{parse_priority, parse_task_type, validate_timeout, MAX_DEPENDENCIES};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Modèle de workflow nommé ("podcast-ingest", "product-photo"...) : une suite
+/// d'étapes développée en tâches dépendantes à chaque exécution
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WorkflowTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// Étape d'un workflow, avec les mêmes options qu'une tâche créée par `POST /tasks`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WorkflowStep {
+    /// Identifiant de l'étape, unique dans le modèle
+    pub id: String,
+    pub task_type: String,  // "video", "audio", "image"
+    #[serde(default)]
+    pub options: TaskOptionsDto,
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Étapes précédentes qui doivent réussir avant celle-ci
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Étape dont la sortie sert de fichier d'entrée ; sans elle, l'étape
+    /// traite le fichier fourni à l'exécution
+    #[serde(default)]
+    pub input_from: Option<String>,
+    #[serde(default)]
+    pub on_dependency_failure: Option<String>,  // "cancel" (défaut), "fail"
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Error, Debug)]
+pub enum WorkflowError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    
+    #[error("Invalid workflow file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    
+    #[error("Invalid workflow file {path}: {source}")]
+    ParseYaml {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+    
+    #[error("Invalid workflow {name}: {reason}")]
+    Invalid {
+        name: String,
+        reason: String,
+    },
+}
+
+impl WorkflowStep {
+    /// Étapes dont celle-ci dépend, y compris celle qui fournit son entrée
+    pub fn dependencies(&self) -> Vec<&str> {
+        let mut dependencies: Vec<&str> = self.depends_on.iter().map(String::as_str).collect();
+        if let Some(ref input_from) = self.input_from {
+            if !dependencies.contains(&input_from.as_str()) {
+                dependencies.push(input_from);
+            }
+        }
+        dependencies
+    }
+}
+
+impl WorkflowTemplate {
+    /// Vérifie le modèle au chargement, pour qu'une exécution ne puisse pas
+    /// échouer sur une erreur de définition
+    pub fn validate(&self) -> Result<(), WorkflowError> {
+        let invalid = |reason: String| WorkflowError::Invalid { name: self.name.clone(), reason };
+        
+        if self.name.is_empty() {
+            return Err(invalid("name cannot be empty".to_string()));
+        }
+        if self.steps.is_empty() {
+            return Err(invalid("a workflow must have at least one step".to_string()));
+        }
+        
+        let mut previous = HashSet::new();
+        for step in &self.steps {
+            if step.id.is_empty() {
+                return Err(invalid("step id cannot be empty".to_string()));
+            }
+            
+            parse_task_type(&step.task_type).map_err(|e| invalid(format!("step {}: {}", step.id, e)))?;
+            parse_priority(step.priority.as_deref()).map_err(|e| invalid(format!("step {}: {}", step.id, e)))?;
+            validate_timeout(step.timeout_seconds).map_err(|e| invalid(format!("step {}: {}", step.id, e)))?;
+            if let Some(ref policy) = step.on_dependency_failure {
+                if !["cancel", "fail"].contains(&policy.as_str()) {
+                    return Err(invalid(format!("step {}: invalid on_dependency_failure: {}", step.id, policy)));
+                }
+            }
+            
+            // Ne référencer que des étapes précédentes exclut les cycles
+            let dependencies = step.dependencies();
+            if dependencies.len() > MAX_DEPENDENCIES {
+                return Err(invalid(format!("step {} has too many dependencies", step.id)));
+            }
+            if let Some(unknown) = dependencies.iter().find(|dependency| !previous.contains(*dependency)) {
+                return Err(invalid(format!("step {} depends on unknown or later step {}", step.id, unknown)));
+            }
+            
+            if !previous.insert(step.id.as_str()) {
+                return Err(invalid(format!("duplicate step id {}", step.id)));
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Charge les modèles `*.json`, `*.yaml` et `*.yml` du répertoire ; un
+/// répertoire absent n'est pas une erreur, un modèle invalide en est une
+pub fn load_templates(dir: &Path) -> Result<HashMap<String, WorkflowTemplate>, WorkflowError> {
+    let mut templates = HashMap::new();
+    
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!("Workflow directory {} not found, no workflow loaded", dir.display());
+            return Ok(templates);
+        }
+        Err(source) => return Err(WorkflowError::Io { path: dir.to_path_buf(), source }),
+    };
+    
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|source| WorkflowError::Io { path: dir.to_path_buf(), source })?.path();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json" | "yaml" | "yml") => paths.push(path),
+            _ if path.is_file() => {
+                tracing::warn!("Ignoring {}: workflow templates must be .json, .yaml or .yml", path.display());
+            }
+            _ => {}
+        }
+    }
+    paths.sort();
+    
+    for path in paths {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|source| WorkflowError::Io { path: path.clone(), source })?;
+        let template: WorkflowTemplate = if path.extension().is_some_and(|extension| extension == "json") {
+            serde_json::from_str(&content)
+                .map_err(|source| WorkflowError::Parse { path: path.clone(), source })?
+        } else {
+            serde_yaml::from_str(&content)
+                .map_err(|source| WorkflowError::ParseYaml { path: path.clone(), source })?
+        };
+        
+        template.validate()?;
+        
+        if templates.contains_key(&template.name) {
+            return Err(WorkflowError::Invalid {
+                name: template.name,
+                reason: format!("defined twice ({})", path.display()),
+            });
+        }
+        
+        tracing::info!("Loaded workflow {} ({} steps)", template.name, template.steps.len());
+        templates.insert(template.name.clone(), template);
+    }
+    
+    Ok(templates)
+}
@@ -3,19 +3,45 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub struct DashboardStats {
     pub total_tasks: u64,
+    pub scheduled_tasks: u64,
+    pub blocked_tasks: u64,
     pub pending_tasks: u64,
+    pub queued_tasks: u64,
     pub processing_tasks: u64,
+    pub paused_tasks: u64,
+    pub retrying_tasks: u64,
+    pub cancelling_tasks: u64,
     pub completed_tasks: u64,
     pub failed_tasks: u64,
     pub cancelled_tasks: u64,
+    pub expired_tasks: u64,
     pub queue_lengths: QueueLengths,
+    pub dead_letter_lengths: DeadLetterLengths,
 }
 
 #[derive(Debug, Serialize)]
 pub struct QueueLengths {
+    pub video: LaneLengths,
+    pub audio: LaneLengths,
+    pub image: LaneLengths,
+}
+
+/// Profondeur d'une queue, détaillée par lane de priorité
+#[derive(Debug, Default, Serialize)]
+pub struct LaneLengths {
+    pub high: i64,
+    pub normal: i64,
+    pub low: i64,
+    pub total: i64,
+}
+
+/// Taille des dead-letter queues par type de tâche
+#[derive(Debug, Default, Serialize)]
+pub struct DeadLetterLengths {
     pub video: i64,
     pub audio: i64,
     pub image: i64,
+    pub total: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -23,8 +49,40 @@ pub struct TaskSummary {
     pub id: String,
     pub task_type: String,
     pub status: String,
+    pub priority: String,
     pub progress: f32,
     pub created_at: String,
     pub updated_at: String,
     pub error: Option<String>,
+    pub retry_count: u32,
+    pub next_retry_at: Option<String>,
+    pub job_id: Option<String>,
+}
+
+/// Transition de statut d'une tâche, pour la timeline
+#[derive(Debug, Serialize)]
+pub struct TransitionSummary {
+    pub from: String,
+    pub to: String,
+    pub at: String,
+    pub worker_id: Option<String>,
+    pub attempt: u32,
+    pub error: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Worker du registre, avec son état de liveness
+#[derive(Debug, Serialize)]
+pub struct WorkerSummary {
+    pub worker_id: String,
+    pub task_types: Vec<String>,
+    pub hostname: String,
+    pub version: String,
+    pub started_at: String,
+    pub last_heartbeat: String,
+    pub concurrency: usize,
+    pub current_task_ids: Vec<String>,
+    pub processed_count: u64,
+    pub failed_count: u64,
+    pub alive: bool,
 }
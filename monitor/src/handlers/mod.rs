@@ -1,7 +1,16 @@
-use crate::dtos::{DashboardStats, QueueLengths, TaskSummary};
+use crate::dtos::{DashboardStats, DeadLetterLengths, LaneLengths, QueueLengths, TaskSummary, TransitionSummary, WorkerSummary};
 use crate::state::AppState;
-use axum::{extract::State, Json};
-use shared::Task;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::{Stream, StreamExt};
+use shared::pubsub::EVENTS_CHANNEL;
+use shared::{queue, PubSubClient, Task, TaskPriority, TaskType, WorkerInfo};
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// GET /api/stats - Statistiques globales
@@ -16,16 +25,46 @@ pub async fn get_stats(
         .await
         .unwrap_or(0);
     
+    let scheduled = collection
+        .count_documents(mongodb::bson::doc! { "status": "scheduled" }, None)
+        .await
+        .unwrap_or(0);
+    
+    let blocked = collection
+        .count_documents(mongodb::bson::doc! { "status": "blocked" }, None)
+        .await
+        .unwrap_or(0);
+    
     let pending = collection
         .count_documents(mongodb::bson::doc! { "status": "pending" }, None)
         .await
         .unwrap_or(0);
     
+    let queued = collection
+        .count_documents(mongodb::bson::doc! { "status": "queued" }, None)
+        .await
+        .unwrap_or(0);
+    
     let processing = collection
         .count_documents(mongodb::bson::doc! { "status": "processing" }, None)
         .await
         .unwrap_or(0);
     
+    let paused = collection
+        .count_documents(mongodb::bson::doc! { "status": "paused" }, None)
+        .await
+        .unwrap_or(0);
+    
+    let retrying = collection
+        .count_documents(mongodb::bson::doc! { "status": "retrying" }, None)
+        .await
+        .unwrap_or(0);
+    
+    let cancelling = collection
+        .count_documents(mongodb::bson::doc! { "status": "cancelling" }, None)
+        .await
+        .unwrap_or(0);
+    
     let completed = collection
         .count_documents(mongodb::bson::doc! { "status": "completed" }, None)
         .await
@@ -41,50 +80,79 @@ pub async fn get_stats(
         .await
         .unwrap_or(0);
     
+    let expired = collection
+        .count_documents(mongodb::bson::doc! { "status": "expired" }, None)
+        .await
+        .unwrap_or(0);
+    
     // Longueurs des queues Redis
     let queue_lengths = get_queue_lengths(&state).await;
+    let dead_letter_lengths = get_dead_letter_lengths(&state).await;
     
     Json(DashboardStats {
         total_tasks,
+        scheduled_tasks: scheduled,
+        blocked_tasks: blocked,
         pending_tasks: pending,
+        queued_tasks: queued,
         processing_tasks: processing,
+        paused_tasks: paused,
+        retrying_tasks: retrying,
+        cancelling_tasks: cancelling,
         completed_tasks: completed,
         failed_tasks: failed,
         cancelled_tasks: cancelled,
+        expired_tasks: expired,
         queue_lengths,
+        dead_letter_lengths,
     })
 }
 
+#[derive(Deserialize)]
+pub struct RecentTasksQuery {
+    /// Ne garder que les tâches de ce job
+    pub job_id: Option<String>,
+}
+
 /// GET /api/tasks/recent - Tâches récentes
 pub async fn get_recent_tasks(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<RecentTasksQuery>,
 ) -> Json<Vec<TaskSummary>> {
     let db = state.get_database();
     let collection = db.collection::<Task>("tasks");
     
+    let mut filter = mongodb::bson::doc! {};
+    if let Some(job_id) = query.job_id.filter(|job_id| !job_id.is_empty()) {
+        filter.insert("job_id", job_id);
+    }
+    
     let options = mongodb::options::FindOptions::builder()
         .sort(mongodb::bson::doc! { "created_at": -1 })
         .limit(50)
         .build();
     
     let mut cursor = collection
-        .find(mongodb::bson::doc! {}, options)
+        .find(filter, options)
         .await
         .unwrap();
     
     let mut tasks = Vec::new();
     
-    use futures::stream::StreamExt;
     while let Some(result) = cursor.next().await {
         if let Ok(task) = result {
             tasks.push(TaskSummary {
                 id: task.id,
                 task_type: task.task_type.to_string(),
                 status: task.status.to_string(),
+                priority: task.priority.to_string(),
                 progress: task.progress,
                 created_at: task.created_at.to_rfc3339(),
                 updated_at: task.updated_at.to_rfc3339(),
                 error: task.error,
+                retry_count: task.retry_count,
+                next_retry_at: task.next_retry_at.map(|retry_at| retry_at.to_rfc3339()),
+                job_id: task.job_id,
             });
         }
     }
@@ -92,39 +160,155 @@ pub async fn get_recent_tasks(
     Json(tasks)
 }
 
+/// GET /api/tasks/:id/history - Timeline des statuts d'une tâche
+pub async fn get_task_history(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<Vec<TransitionSummary>>, StatusCode> {
+    let db = state.get_database();
+    let collection = db.collection::<Task>("tasks");
+    
+    let task = collection
+        .find_one(mongodb::bson::doc! { "task_id": &task_id }, None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    
+    let transitions = task
+        .history
+        .into_iter()
+        .map(|transition| TransitionSummary {
+            from: transition.from.to_string(),
+            to: transition.to.to_string(),
+            at: transition.at.to_rfc3339(),
+            worker_id: transition.worker_id,
+            attempt: transition.attempt,
+            error: transition.error,
+            reason: transition.reason,
+        })
+        .collect();
+    
+    Ok(Json(transitions))
+}
+
+/// GET /api/workers - Registre des workers
+pub async fn get_workers(
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<WorkerSummary>> {
+    let mut conn = match state.redis_client.get_multiplexed_async_connection().await {
+        Ok(c) => c,
+        Err(_) => return Json(Vec::new()),
+    };
+    
+    let entries: HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(queue::WORKERS_KEY)
+        .query_async(&mut conn)
+        .await
+        .unwrap_or_default();
+    
+    let mut workers: Vec<WorkerSummary> = entries
+        .values()
+        .filter_map(|json| serde_json::from_str::<WorkerInfo>(json).ok())
+        .map(|worker| WorkerSummary {
+            alive: worker.is_alive(),
+            worker_id: worker.worker_id,
+            task_types: worker.task_types.iter().map(|task_type| task_type.to_string()).collect(),
+            hostname: worker.hostname,
+            version: worker.version,
+            started_at: worker.started_at.to_rfc3339(),
+            last_heartbeat: worker.last_heartbeat.to_rfc3339(),
+            concurrency: worker.concurrency,
+            current_task_ids: worker.current_task_ids,
+            processed_count: worker.processed_count,
+            failed_count: worker.failed_count,
+        })
+        .collect();
+    
+    // Les workers morts en tête pour qu'ils sautent aux yeux
+    workers.sort_by(|a, b| a.alive.cmp(&b.alive).then_with(|| a.worker_id.cmp(&b.worker_id)));
+    
+    Json(workers)
+}
+
+/// GET /api/events - Événements de toutes les tâches en Server-Sent Events
+pub async fn get_events(
+    State(state): State<Arc<AppState>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, serde_json::Error>>>, StatusCode> {
+    let events = PubSubClient::new(state.redis_client.clone())
+        .subscribe_events(vec![EVENTS_CHANNEL.to_string()])
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    
+    let stream = events.map(|event| Ok(Event::default().event(event.kind()).data(event.to_json()?)));
+    
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
 async fn get_queue_lengths(state: &AppState) -> QueueLengths {
-    let mut conn = match state.redis_client.get_async_connection().await {
+    let mut conn = match state.redis_client.get_multiplexed_async_connection().await {
         Ok(c) => c,
         Err(_) => {
             return QueueLengths {
-                video: 0,
-                audio: 0,
-                image: 0,
+                video: LaneLengths::default(),
+                audio: LaneLengths::default(),
+                image: LaneLengths::default(),
             }
         }
     };
     
-    let video: i64 = redis::cmd("LLEN")
-        .arg("queue:video")
-        .query_async(&mut conn)
-        .await
-        .unwrap_or(0);
+    QueueLengths {
+        video: get_lane_lengths(&mut conn, &TaskType::VideoCompression).await,
+        audio: get_lane_lengths(&mut conn, &TaskType::AudioProcessing).await,
+        image: get_lane_lengths(&mut conn, &TaskType::ImageOptimization).await,
+    }
+}
+
+async fn get_dead_letter_lengths(state: &AppState) -> DeadLetterLengths {
+    let mut conn = match state.redis_client.get_multiplexed_async_connection().await {
+        Ok(c) => c,
+        Err(_) => return DeadLetterLengths::default(),
+    };
     
-    let audio: i64 = redis::cmd("LLEN")
-        .arg("queue:audio")
-        .query_async(&mut conn)
-        .await
-        .unwrap_or(0);
+    let mut lengths = DeadLetterLengths::default();
     
-    let image: i64 = redis::cmd("LLEN")
-        .arg("queue:image")
-        .query_async(&mut conn)
-        .await
-        .unwrap_or(0);
+    for task_type in [TaskType::VideoCompression, TaskType::AudioProcessing, TaskType::ImageOptimization] {
+        let len: i64 = redis::cmd("HLEN")
+            .arg(queue::dead_letter_key(&task_type))
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(0);
+        
+        match task_type {
+            TaskType::VideoCompression => lengths.video = len,
+            TaskType::AudioProcessing => lengths.audio = len,
+            TaskType::ImageOptimization => lengths.image = len,
+        }
+        lengths.total += len;
+    }
     
-    QueueLengths {
-        video,
-        audio,
-        image,
+    lengths
+}
+
+async fn get_lane_lengths(
+    conn: &mut redis::aio::MultiplexedConnection,
+    task_type: &TaskType,
+) -> LaneLengths {
+    let mut lengths = LaneLengths::default();
+    
+    for priority in TaskPriority::ALL {
+        let len: i64 = redis::cmd("LLEN")
+            .arg(queue::lane_key(task_type, priority))
+            .query_async(conn)
+            .await
+            .unwrap_or(0);
+        
+        match priority {
+            TaskPriority::High => lengths.high = len,
+            TaskPriority::Normal => lengths.normal = len,
+            TaskPriority::Low => lengths.low = len,
+        }
+        lengths.total += len;
     }
+    
+    lengths
 }
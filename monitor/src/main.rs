@@ -49,10 +49,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("📊 Dashboard: http://{}/", addr);
     tracing::info!("📡 API Stats: http://{}/api/stats", addr);
     tracing::info!("📋 Recent Tasks: http://{}/api/tasks/recent", addr);
+    tracing::info!("🕓 Task History: http://{}/api/tasks/:id/history", addr);
+    tracing::info!("📣 Task Events: http://{}/api/events", addr);
     
     axum::serve(listener, app)
+        .with_graceful_shutdown(shared::utils::shutdown_signal())
         .await
         .expect("Failed to start server");
     
+    tracing::info!("Monitor Dashboard stopped");
+    
     Ok(())
 }
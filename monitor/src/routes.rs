@@ -15,6 +15,9 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // API routes
         .route("/api/stats", get(handlers::get_stats))
         .route("/api/tasks/recent", get(handlers::get_recent_tasks))
+        .route("/api/tasks/:id/history", get(handlers::get_task_history))
+        .route("/api/workers", get(handlers::get_workers))
+        .route("/api/events", get(handlers::get_events))
         
         // Serve static files (dashboard HTML/CSS/JS)
         .nest_service("/", ServeDir::new("monitor/static"))
@@ -25,7 +25,7 @@ impl AppState {
         
         let redis_client = RedisClient::open(redis_uri)?;
         
-        let mut conn = redis_client.get_async_connection().await?;
+        let mut conn = redis_client.get_multiplexed_async_connection().await?;
         let _: String = redis::cmd("PING")
             .query_async(&mut conn)
             .await?;
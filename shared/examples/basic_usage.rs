@@ -22,7 +22,7 @@ fn main() {
 
     // Test 2: Update task status
     println!("✅ Test 2: Updating task status");
-    task.update_status(TaskStatus::Processing);
+    task.update_status(TaskStatus::Processing).expect("pending -> processing is allowed");
     println!("   New status: {:?}", task.status);
     println!("   Started at: {:?}\n", task.started_at);
 
@@ -33,7 +33,7 @@ fn main() {
 
     // Test 4: Complete task
     println!("✅ Test 4: Completing task");
-    task.update_status(TaskStatus::Completed);
+    task.update_status(TaskStatus::Completed).expect("processing -> completed is allowed");
     println!("   Status: {:?}", task.status);
     println!("   Completed at: {:?}\n", task.completed_at);
 
@@ -51,12 +51,15 @@ fn main() {
     let mut task2 = Task::new(TaskType::AudioProcessing, media2);
     println!("   Can retry: {}", task2.can_retry());
     
-    task2.mark_failed("Connection timeout".to_string());
+    task2.increment_retry();
+    task2.record_error("Connection timeout".to_string());
     println!("   After 1st failure - Retry count: {}, Can retry: {}", 
              task2.retry_count, task2.can_retry());
     
-    task2.mark_failed("Connection timeout".to_string());
-    task2.mark_failed("Connection timeout".to_string());
+    for _ in 0..2 {
+        task2.increment_retry();
+        task2.record_error("Connection timeout".to_string());
+    }
     println!("   After 3rd failure - Retry count: {}, Can retry: {}", 
              task2.retry_count, task2.can_retry());
 
@@ -0,0 +1,214 @@
+use crate::models::{DependencyFailurePolicy, InvalidTransition, Task, TaskStatus};
+use crate::queue;
+use crate::utils::mongo_utils::{status_filter, task_update};
+use chrono::Utc;
+use mongodb::bson::doc;
+use mongodb::Database;
+use redis::aio::ConnectionLike;
+use std::path::PathBuf;
+
+/// Erreurs de la résolution des dépendances
+#[derive(Debug, thiserror::Error)]
+pub enum DependencyError {
+    #[error("MongoDB error: {0}")]
+    Mongo(#[from] mongodb::error::Error),
+
+    #[error("BSON serialization error: {0}")]
+    Bson(#[from] mongodb::bson::ser::Error),
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Transition(#[from] InvalidTransition),
+}
+
+/// Ce qu'il advient d'une tâche après examen de ses dépendances
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyOutcome {
+    /// La tâche n'est pas (ou plus) bloquée
+    NotBlocked,
+    /// Au moins une dépendance n'est pas terminée
+    Waiting,
+    /// Toutes les dépendances ont réussi, la tâche est enfilée
+    Released,
+    /// Une dépendance a échoué ou a été annulée, la tâche est annulée ou en échec
+    Aborted,
+}
+
+/// État des dépendances d'une tâche bloquée
+#[derive(Debug, PartialEq, Eq)]
+enum ParentsState {
+    Waiting,
+    Ready,
+    /// Raison pour laquelle la tâche ne pourra jamais être exécutée
+    Broken(String),
+}
+
+fn parents_state(task: &Task, parents: &[Task]) -> ParentsState {
+    for id in &task.depends_on {
+        match parents.iter().find(|parent| &parent.id == id) {
+            None => return ParentsState::Broken(format!("Dependency {} not found", id)),
+            Some(parent) if matches!(parent.status, TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Expired) => {
+                return ParentsState::Broken(format!("Dependency {} {}", id, parent.status));
+            }
+            _ => {}
+        }
+    }
+
+    if parents.iter().all(|parent| parent.status == TaskStatus::Completed) {
+        ParentsState::Ready
+    } else {
+        ParentsState::Waiting
+    }
+}
+
+/// Examine les dépendances d'une tâche bloquée : l'enfile si elles ont toutes
+/// réussi, applique sa politique d'échec si l'une d'elles a échoué.
+///
+/// L'écriture n'a lieu que si la tâche est encore `blocked` dans MongoDB :
+/// plusieurs appels concurrents pour la même tâche sont sans danger.
+pub async fn release_if_ready<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    task_id: &str,
+) -> Result<DependencyOutcome, DependencyError> {
+    let collection = db.collection::<Task>("tasks");
+
+    let Some(mut task) = collection.find_one(doc! { "task_id": task_id }, None).await? else {
+        return Ok(DependencyOutcome::NotBlocked);
+    };
+    if task.status != TaskStatus::Blocked {
+        return Ok(DependencyOutcome::NotBlocked);
+    }
+
+    let mut parents = Vec::new();
+    let mut cursor = collection
+        .find(doc! { "task_id": { "$in": &task.depends_on } }, None)
+        .await?;
+    while cursor.advance().await? {
+        parents.push(cursor.deserialize_current()?);
+    }
+
+    let mut state = parents_state(&task, &parents);
+
+    // Le fichier d'entrée est la sortie de la dépendance désignée
+    if let (ParentsState::Ready, Some(input_from)) = (&state, &task.input_from) {
+        match parents.iter().find(|parent| &parent.id == input_from).and_then(|parent| parent.output_path.clone()) {
+            Some(output_path) => task.media.file_path = PathBuf::from(output_path),
+            None => state = ParentsState::Broken(format!("Dependency {} produced no output", input_from)),
+        }
+    }
+
+    let outcome = match state {
+        ParentsState::Waiting => return Ok(DependencyOutcome::Waiting),
+        ParentsState::Ready => {
+            let deferred = task.run_at.is_some_and(|run_at| run_at > Utc::now());
+            task.update_status(if deferred { TaskStatus::Scheduled } else { TaskStatus::Queued })?;
+            DependencyOutcome::Released
+        }
+        ParentsState::Broken(reason) => {
+            task.record_error(reason);
+            task.update_status(match task.on_dependency_failure {
+                DependencyFailurePolicy::Cancel => TaskStatus::Cancelled,
+                DependencyFailurePolicy::Fail => TaskStatus::Failed,
+            })?;
+            DependencyOutcome::Aborted
+        }
+    };
+
+    let result = collection
+        .update_one(status_filter(&task.id, &[TaskStatus::Blocked]), task_update(&task)?, None)
+        .await?;
+    if result.matched_count == 0 {
+        // Débloquée ou annulée entre-temps
+        return Ok(DependencyOutcome::NotBlocked);
+    }
+
+    if outcome == DependencyOutcome::Released {
+        let mut pipe = redis::pipe();
+        match task.run_at {
+            Some(run_at) if task.status == TaskStatus::Scheduled => {
+                queue::schedule_task(pipe.atomic(), &task, run_at.timestamp_millis());
+            }
+            _ => queue::push_task(pipe.atomic(), &task),
+        }
+        let _: () = pipe.query_async(conn).await?;
+    }
+
+    tracing::info!(task_id = %task.id, status = %task.status, "Blocked task resolved");
+
+    Ok(outcome)
+}
+
+/// À appeler quand une tâche atteint un statut terminal : examine ses
+/// dépendants bloqués, et en cascade ceux des dépendants qu'elle fait échouer
+pub async fn settle_dependents<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    task_id: &str,
+) -> Result<(), DependencyError> {
+    let collection = db.collection::<Task>("tasks");
+    let mut finished = vec![task_id.to_string()];
+
+    while let Some(parent_id) = finished.pop() {
+        let filter = doc! { "depends_on": &parent_id, "status": TaskStatus::Blocked.to_string() };
+        let mut dependents = Vec::new();
+        let mut cursor = collection.find(filter, None).await?;
+        while cursor.advance().await? {
+            dependents.push(cursor.deserialize_current()?.id);
+        }
+
+        for dependent in dependents {
+            if release_if_ready(db, conn, &dependent).await? == DependencyOutcome::Aborted {
+                finished.push(dependent);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MediaFile, MediaType, TaskType};
+
+    fn task(status: TaskStatus) -> Task {
+        let media = MediaFile::new(
+            "test-123".to_string(),
+            MediaType::Audio,
+            PathBuf::from("/path/to/audio.mp3"),
+            4096,
+            "audio.mp3".to_string(),
+            "audio/mpeg".to_string(),
+        );
+        let mut task = Task::new(TaskType::AudioProcessing, media);
+        task.status = status;
+        task
+    }
+
+    #[test]
+    fn test_parents_state() {
+        let extract = task(TaskStatus::Completed);
+        let normalize = task(TaskStatus::Processing);
+
+        let mut waveform = task(TaskStatus::Blocked);
+        waveform.depends_on = vec![extract.id.clone(), normalize.id.clone()];
+
+        let mut parents = vec![extract, normalize];
+        assert_eq!(parents_state(&waveform, &parents), ParentsState::Waiting);
+
+        parents[1].status = TaskStatus::Completed;
+        assert_eq!(parents_state(&waveform, &parents), ParentsState::Ready);
+
+        parents[1].status = TaskStatus::Failed;
+        assert!(matches!(parents_state(&waveform, &parents), ParentsState::Broken(_)));
+
+        // Une dépendance disparue ne sera jamais satisfaite
+        assert!(matches!(parents_state(&waveform, &parents[..1]), ParentsState::Broken(_)));
+    }
+}
@@ -1,7 +1,13 @@
 pub mod models;
 pub mod utils;
 pub mod pubsub;
+pub mod queue;
+pub mod retry;
+pub mod dependencies;
+pub mod outbox;
+pub mod recovery;
+pub mod progress;
 
 // Re-export commonly used types
-pub use models::{Task, TaskStatus, TaskType, MediaFile, MediaType};
-pub use pubsub::{PubSubClient, TaskCommand};
+pub use models::{InvalidTransition, StatusTransition, Task, TaskPriority, TaskStatus, TaskType, MediaFile, MediaType, DeadLetter, DeadLetterReason, WorkerInfo, Job, JobStatus};
+pub use pubsub::{PubSubClient, TaskCommand, TaskEvent};
@@ -0,0 +1,109 @@
+use crate::models::task::{Task, TaskType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Entrée de la dead-letter queue d'un type de tâche
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// task_id, ou identifiant généré quand le payload est illisible
+    pub id: String,
+    pub task_type: TaskType,
+    pub reason: DeadLetterReason,
+    /// Tâche dans son état final, absente si le payload n'a pas pu être lu
+    pub task: Option<Task>,
+    /// Payload final tel qu'il était en queue
+    pub payload: String,
+    /// Historique des erreurs, de la plus ancienne à la plus récente
+    pub errors: Vec<String>,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterReason {
+    /// Toutes les tentatives ont échoué
+    MaxRetriesExceeded,
+    /// Le payload n'a pas pu être désérialisé
+    InvalidPayload,
+}
+
+impl DeadLetter {
+    /// Dead-letter d'une tâche ayant épuisé ses retries
+    pub fn from_failed_task(task: &Task, payload: String) -> Self {
+        Self {
+            id: task.id.clone(),
+            task_type: task.task_type.clone(),
+            reason: DeadLetterReason::MaxRetriesExceeded,
+            task: Some(task.clone()),
+            payload,
+            errors: task.error_history.clone(),
+            dead_lettered_at: Utc::now(),
+        }
+    }
+
+    /// Dead-letter d'un payload illisible trouvé dans une queue
+    pub fn from_invalid_payload(task_type: TaskType, payload: String, error: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            task_type,
+            reason: DeadLetterReason::InvalidPayload,
+            task: None,
+            payload,
+            errors: vec![error],
+            dead_lettered_at: Utc::now(),
+        }
+    }
+}
+
+impl std::fmt::Display for DeadLetterReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeadLetterReason::MaxRetriesExceeded => write!(f, "max_retries_exceeded"),
+            DeadLetterReason::InvalidPayload => write!(f, "invalid_payload"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::media::{MediaFile, MediaType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_dead_letter_from_failed_task() {
+        let media = MediaFile::new(
+            "test-123".to_string(),
+            MediaType::Audio,
+            PathBuf::from("/path/to/audio.mp3"),
+            512000,
+            "audio.mp3".to_string(),
+            "audio/mp3".to_string(),
+        );
+
+        let mut task = Task::new(TaskType::AudioProcessing, media);
+        task.record_error("decoder crashed".to_string());
+        task.record_error("decoder crashed again".to_string());
+
+        let entry = DeadLetter::from_failed_task(&task, "{}".to_string());
+
+        assert_eq!(entry.id, task.id);
+        assert_eq!(entry.reason, DeadLetterReason::MaxRetriesExceeded);
+        assert_eq!(entry.errors, vec!["decoder crashed", "decoder crashed again"]);
+        assert!(entry.task.is_some());
+    }
+
+    #[test]
+    fn test_dead_letter_from_invalid_payload() {
+        let entry = DeadLetter::from_invalid_payload(
+            TaskType::VideoCompression,
+            "not json".to_string(),
+            "expected value".to_string(),
+        );
+
+        assert_eq!(entry.reason, DeadLetterReason::InvalidPayload);
+        assert!(entry.task.is_none());
+        assert_eq!(entry.payload, "not json");
+    }
+}
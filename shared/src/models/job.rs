@@ -0,0 +1,135 @@
+use crate::models::task::{Task, TaskStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Groupe de tâches suivies ensemble (un album, les renditions d'une vidéo...).
+/// Les tâches portent le `job_id` ; le statut du job est calculé à partir d'elles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    #[serde(rename = "job_id")]
+    pub id: String,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Date de la demande d'annulation de toutes les tâches
+    #[serde(default)]
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+/// Statut agrégé des tâches d'un job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Aucune tâche n'a encore commencé
+    Pending,
+    /// Au moins une tâche a commencé et toutes ne sont pas terminées
+    Running,
+    /// Toutes les tâches ont réussi
+    Completed,
+    /// Toutes les tâches sont terminées, certaines seulement ont réussi
+    PartiallyCompleted,
+    /// Toutes les tâches sont terminées, aucune n'a réussi et au moins une a échoué ou expiré
+    Failed,
+    /// Toutes les tâches ont été annulées
+    Cancelled,
+}
+
+impl Job {
+    pub fn new(name: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            created_at: Utc::now(),
+            cancelled_at: None,
+        }
+    }
+
+    /// Progression combinée des tâches du job : une tâche terminée compte pour
+    /// 1.0, quelle que soit son issue
+    pub fn progress(tasks: &[Task]) -> f32 {
+        if tasks.is_empty() {
+            return 0.0;
+        }
+
+        let total: f32 = tasks
+            .iter()
+            .map(|task| if task.status.is_terminal() { 1.0 } else { task.progress })
+            .sum();
+        total / tasks.len() as f32
+    }
+}
+
+impl JobStatus {
+    pub fn aggregate<'a>(statuses: impl IntoIterator<Item = &'a TaskStatus>) -> Self {
+        let statuses: Vec<&TaskStatus> = statuses.into_iter().collect();
+        let count = |status: TaskStatus| statuses.iter().filter(|s| ***s == status).count();
+
+        if statuses.is_empty() {
+            return JobStatus::Pending;
+        }
+
+        if statuses.iter().all(|status| status.is_terminal()) {
+            let completed = count(TaskStatus::Completed);
+            return if completed == statuses.len() {
+                JobStatus::Completed
+            } else if completed > 0 {
+                JobStatus::PartiallyCompleted
+            } else if count(TaskStatus::Cancelled) == statuses.len() {
+                JobStatus::Cancelled
+            } else {
+                JobStatus::Failed
+            };
+        }
+
+        // Une tâche bloquée sur ses dépendances ou en pause n'a pas démarré
+        let waiting = statuses.iter().all(|status| {
+            matches!(
+                status,
+                TaskStatus::Pending
+                    | TaskStatus::Scheduled
+                    | TaskStatus::Queued
+                    | TaskStatus::Blocked
+                    | TaskStatus::Paused
+            )
+        });
+        if waiting {
+            JobStatus::Pending
+        } else {
+            JobStatus::Running
+        }
+    }
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobStatus::Pending => write!(f, "pending"),
+            JobStatus::Running => write!(f, "running"),
+            JobStatus::Completed => write!(f, "completed"),
+            JobStatus::PartiallyCompleted => write!(f, "partially_completed"),
+            JobStatus::Failed => write!(f, "failed"),
+            JobStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_status_aggregate() {
+        use TaskStatus::*;
+
+        assert_eq!(JobStatus::aggregate(&[]), JobStatus::Pending);
+        assert_eq!(JobStatus::aggregate(&[Queued, Scheduled]), JobStatus::Pending);
+        assert_eq!(JobStatus::aggregate(&[Blocked, Blocked]), JobStatus::Pending);
+        assert_eq!(JobStatus::aggregate(&[Queued, Blocked, Paused]), JobStatus::Pending);
+        assert_eq!(JobStatus::aggregate(&[Queued, Processing]), JobStatus::Running);
+        assert_eq!(JobStatus::aggregate(&[Completed, Retrying]), JobStatus::Running);
+        assert_eq!(JobStatus::aggregate(&[Completed, Completed]), JobStatus::Completed);
+        assert_eq!(JobStatus::aggregate(&[Completed, Failed]), JobStatus::PartiallyCompleted);
+        assert_eq!(JobStatus::aggregate(&[Cancelled, Failed]), JobStatus::Failed);
+        assert_eq!(JobStatus::aggregate(&[Cancelled, Cancelled]), JobStatus::Cancelled);
+    }
+}
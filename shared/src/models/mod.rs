@@ -1,5 +1,11 @@
 pub mod task;
 pub mod media;
+pub mod dead_letter;
+pub mod worker;
+pub mod job;
 
-pub use task::{Task, TaskStatus, TaskType};
+pub use task::{DependencyFailurePolicy, InvalidTransition, StatusTransition, Task, TaskPriority, TaskStatus, TaskType, INTERRUPTED_BY_SHUTDOWN};
 pub use media::{MediaFile, MediaType};
+pub use dead_letter::{DeadLetter, DeadLetterReason};
+pub use worker::WorkerInfo;
+pub use job::{Job, JobStatus};
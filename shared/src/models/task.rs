@@ -1,8 +1,8 @@
-use crate::models::media::{MediaFile, MediaType};  
+use crate::models::media::MediaFile;
+use crate::outbox::OutboxEntry;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
 use chrono::{DateTime, Utc};
+use std::time::Duration;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,8 +12,13 @@ pub struct Task {
     pub task_type: TaskType,
     pub media: MediaFile,
     pub status: TaskStatus,
+    #[serde(default)]
+    pub priority: TaskPriority,
     pub progress: f32,
     pub error: Option<String>,
+    /// Erreurs des tentatives successives (bornées à `MAX_ERROR_HISTORY`)
+    #[serde(default)]
+    pub error_history: Vec<String>,
     pub output_path: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -21,8 +26,71 @@ pub struct Task {
     pub completed_at: Option<DateTime<Utc>>,
     pub retry_count: u32,
     pub max_retries: u32,
+    /// Date à partir de laquelle la tâche peut être exécutée
+    #[serde(default)]
+    pub run_at: Option<DateTime<Utc>>,
+    /// Au-delà, la tâche est abandonnée si elle n'a pas commencé
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Date de la prochaine tentative quand la tâche attend un retry
+    #[serde(default)]
+    pub next_retry_at: Option<DateTime<Utc>>,
+    /// Tâches qui doivent réussir avant que celle-ci soit enfilée
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Dépendance dont l'`output_path` devient le fichier d'entrée de la tâche
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_from: Option<String>,
+    /// Sort de la tâche quand une de ses dépendances échoue ou est annulée
+    #[serde(default)]
+    pub on_dependency_failure: DependencyFailurePolicy,
+    /// Durée maximale d'une tentative (défaut : celui du type de tâche)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// Job auquel appartient la tâche
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    /// Clé d'idempotence fournie par le client à la création (unique)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Empreinte de la requête de création associée à la clé d'idempotence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_fingerprint: Option<String>,
+    /// Worker qui détient la tâche, le temps d'une tentative
+    #[serde(default)]
+    pub worker_id: Option<String>,
+    /// Transitions de statut successives (bornées à `MAX_STATUS_HISTORY`)
+    #[serde(default)]
+    pub history: Vec<StatusTransition>,
+    /// Enqueue Redis pas encore confirmé, écrit avec la tâche et retiré une
+    /// fois la tâche enfilée (voir `outbox`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outbox: Option<OutboxEntry>,
+    /// Transitions pas encore écrites dans MongoDB
+    #[serde(skip)]
+    unsaved_transitions: usize,
 }
 
+/// Une entrée de l'historique des statuts d'une tâche
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+    pub at: DateTime<Utc>,
+    pub worker_id: Option<String>,
+    /// Numéro de la tentative (`retry_count` au moment de la transition)
+    pub attempt: u32,
+    /// Erreur de la tentative, pour les passages en retry ou en échec
+    pub error: Option<String>,
+    /// Motif d'une transition qui n'est pas un échec, par exemple
+    /// `INTERRUPTED_BY_SHUTDOWN`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Motif d'une tâche interrompue par l'arrêt de son worker et remise en attente
+pub const INTERRUPTED_BY_SHUTDOWN: &str = "shutdown";
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskType {
@@ -31,14 +99,161 @@ pub enum TaskType {
     ImageOptimization,
 }
 
+/// Sort d'une tâche bloquée dont une dépendance échoue ou est annulée
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyFailurePolicy {
+    /// La tâche est annulée
+    #[default]
+    Cancel,
+    /// La tâche passe en échec, avec l'erreur de la dépendance
+    Fail,
+}
+
+/// Nombre maximal d'erreurs conservées dans `Task::error_history`
+pub const MAX_ERROR_HISTORY: usize = 20;
+
+/// Nombre maximal de transitions conservées dans `Task::history`
+pub const MAX_STATUS_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl TaskPriority {
+    /// Toutes les priorités, de la plus haute à la plus basse
+    pub const ALL: [TaskPriority; 3] = [TaskPriority::High, TaskPriority::Normal, TaskPriority::Low];
+}
+
+/// Cycle de vie d'une tâche.
+///
+/// ```text
+/// pending ──> queued ──> processing ──> completed
+///    │          ▲  ▲         │  │
+///    ▼          │  │         │  └─────> failed ──(replay)──> queued
+/// scheduled ────┘  │         ▼
+///                  └──── retrying
+///
+/// blocked    : depuis pending, attend la fin de ses dépendances puis part en queued
+///              ou scheduled (annulée ou en échec si une dépendance échoue)
+/// paused     : depuis toute étape non terminale avant completed, reprise vers l'étape d'origine
+/// cancelling : annulation demandée à un worker, se termine en cancelled
+/// expired    : pas commencée avant son `expires_at`, depuis toute étape d'attente
+/// ```
+///
+/// Les transitions autorisées sont décrites par `TaskStatus::can_transition_to`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
+    /// Créée, pas encore enfilée
     Pending,
+    /// En attente de son `run_at`
+    Scheduled,
+    /// En attente de la réussite de ses dépendances (`depends_on`)
+    Blocked,
+    /// Dans une lane Redis, prête à être prise par un worker
+    Queued,
     Processing,
+    /// Suspendue : retenue hors des queues, ou gelée par le worker qui la traite
+    Paused,
+    /// Tentative en échec, en attente de `next_retry_at`
+    Retrying,
+    /// Annulation demandée au worker qui la traite
+    #[serde(alias = "Cancelling")]
+    Cancelling,
     Completed,
     Failed,
+    /// Les anciennes versions de l'API écrivaient "Cancelled"
+    #[serde(alias = "Cancelled")]
     Cancelled,
+    /// Pas commencée avant son `expires_at`, abandonnée sans être traitée
+    Expired,
+}
+
+/// Transition refusée par la machine à états des tâches
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid task status transition: {from} -> {to}")]
+pub struct InvalidTransition {
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+}
+
+impl TaskStatus {
+    /// Tous les statuts, dans l'ordre du cycle de vie
+    pub const ALL: [TaskStatus; 12] = [
+        TaskStatus::Pending,
+        TaskStatus::Scheduled,
+        TaskStatus::Blocked,
+        TaskStatus::Queued,
+        TaskStatus::Processing,
+        TaskStatus::Paused,
+        TaskStatus::Retrying,
+        TaskStatus::Cancelling,
+        TaskStatus::Completed,
+        TaskStatus::Failed,
+        TaskStatus::Cancelled,
+        TaskStatus::Expired,
+    ];
+
+    /// Aucune transition ne sort d'un statut terminal (sauf le replay d'un échec)
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Expired
+        )
+    }
+
+    /// Indique si la machine à états autorise le passage de `self` à `next`.
+    ///
+    /// Rester dans le même statut est toujours permis (simple rafraîchissement).
+    pub fn can_transition_to(&self, next: &TaskStatus) -> bool {
+        use TaskStatus::*;
+
+        if self == next {
+            return true;
+        }
+
+        match self {
+            // Processing direct : tâches enfilées avant l'introduction de `queued`
+            Pending => matches!(
+                next,
+                Queued | Scheduled | Blocked | Processing | Paused | Cancelling | Cancelled | Expired
+            ),
+            Scheduled => matches!(next, Queued | Paused | Cancelling | Cancelled | Expired),
+            // Failed : dépendance en échec avec la politique `fail`
+            Blocked => matches!(next, Queued | Scheduled | Cancelling | Cancelled | Failed | Expired),
+            Queued => matches!(next, Processing | Paused | Cancelling | Cancelled | Expired),
+            // Queued : remise en queue après une interruption (arrêt du worker)
+            Processing => matches!(
+                next,
+                Completed | Failed | Retrying | Queued | Paused | Cancelling | Cancelled
+            ),
+            // Une pause peut arriver alors que le traitement se termine
+            Paused => matches!(
+                next,
+                Queued | Scheduled | Retrying | Processing | Completed | Failed | Cancelling | Cancelled | Expired
+            ),
+            Retrying => matches!(next, Queued | Paused | Cancelling | Cancelled | Expired),
+            // Le worker peut avoir terminé avant de recevoir l'annulation
+            Cancelling => matches!(next, Cancelled | Completed | Failed),
+            // Replay depuis la dead-letter queue
+            Failed => matches!(next, Queued),
+            Completed | Cancelled | Expired => false,
+        }
+    }
+
+    /// Statuts depuis lesquels `self` est atteignable
+    pub fn predecessors(&self) -> Vec<TaskStatus> {
+        TaskStatus::ALL
+            .into_iter()
+            .filter(|from| from.can_transition_to(self))
+            .collect()
+    }
 }
 
 impl Task {
@@ -48,8 +263,10 @@ impl Task {
             task_type,
             media,
             status: TaskStatus::Pending,
+            priority: TaskPriority::default(),
             progress: 0.0,
             error: None,
+            error_history: Vec::new(),
             output_path: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -57,26 +274,78 @@ impl Task {
             completed_at: None,
             retry_count: 0,
             max_retries: 3,
+            run_at: None,
+            expires_at: None,
+            next_retry_at: None,
+            depends_on: Vec::new(),
+            input_from: None,
+            on_dependency_failure: DependencyFailurePolicy::default(),
+            timeout_seconds: None,
+            job_id: None,
+            idempotency_key: None,
+            request_fingerprint: None,
+            worker_id: None,
+            history: Vec::new(),
+            outbox: None,
+            unsaved_transitions: 0,
         }
     }
 
-    pub fn update_status(&mut self, new_status: TaskStatus) {
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Diffère l'exécution : la tâche reste `scheduled` jusqu'à `run_at`
+    pub fn with_run_at(mut self, run_at: DateTime<Utc>) -> Self {
+        self.run_at = Some(run_at);
+        if run_at > Utc::now() && self.status.can_transition_to(&TaskStatus::Scheduled) {
+            self.status = TaskStatus::Scheduled;
+        }
+        self
+    }
+
+    /// Fait passer la tâche à `new_status` si la machine à états l'autorise
+    pub fn update_status(&mut self, new_status: TaskStatus) -> Result<(), InvalidTransition> {
+        if !self.status.can_transition_to(&new_status) {
+            return Err(InvalidTransition {
+                from: self.status.clone(),
+                to: new_status,
+            });
+        }
+
+        if self.status != new_status {
+            self.record_transition(new_status.clone());
+        }
+
         self.status = new_status.clone();
         self.updated_at = Utc::now();
 
         match new_status {
-            TaskStatus::Processing => {
-                if self.started_at.is_none() {
-                    self.started_at = Some(Utc::now());
-                }
+            TaskStatus::Processing if self.started_at.is_none() => {
+                self.started_at = Some(Utc::now());
             }
-            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled => {
-                if self.completed_at.is_none() {
-                    self.completed_at = Some(Utc::now());
-                }
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Expired
+                if self.completed_at.is_none() =>
+            {
+                self.completed_at = Some(Utc::now());
             }
             _ => {}
         }
+
+        Ok(())
+    }
+
+    /// Comme `update_status`, en notant le motif de la transition dans l'historique
+    pub fn update_status_with_reason(&mut self, new_status: TaskStatus, reason: &str) -> Result<(), InvalidTransition> {
+        let changed = self.status != new_status;
+        self.update_status(new_status)?;
+        if changed {
+            if let Some(transition) = self.history.last_mut() {
+                transition.reason = Some(reason.to_string());
+            }
+        }
+        Ok(())
     }
 
     pub fn update_progress(&mut self, progress: f32) {
@@ -84,14 +353,55 @@ impl Task {
         self.updated_at = Utc::now();
     }
 
-    pub fn mark_failed(&mut self, error: String) {
-        self.status = TaskStatus::Failed;
+    /// Attribue la tâche au worker qui s'apprête à la traiter
+    pub fn assign_worker(&mut self, worker_id: &str) {
+        self.worker_id = Some(worker_id.to_string());
+    }
+
+    fn record_transition(&mut self, to: TaskStatus) {
+        let error = match to {
+            TaskStatus::Retrying | TaskStatus::Failed => self.error.clone(),
+            _ => None,
+        };
+
+        if self.history.len() >= MAX_STATUS_HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(StatusTransition {
+            from: self.status.clone(),
+            to: to.clone(),
+            at: Utc::now(),
+            worker_id: self.worker_id.clone(),
+            attempt: self.retry_count,
+            error,
+            reason: None,
+        });
+        self.unsaved_transitions = (self.unsaved_transitions + 1).min(self.history.len());
+
+        // De retour en attente : plus aucun worker ne la détient
+        if matches!(to, TaskStatus::Pending | TaskStatus::Scheduled | TaskStatus::Queued | TaskStatus::Retrying) {
+            self.worker_id = None;
+        }
+    }
+
+    /// Transitions enregistrées depuis la dernière écriture dans MongoDB
+    pub fn unsaved_transitions(&self) -> &[StatusTransition] {
+        &self.history[self.history.len() - self.unsaved_transitions..]
+    }
+
+    /// À appeler une fois les transitions en attente écrites dans MongoDB
+    pub fn mark_transitions_saved(&mut self) {
+        self.unsaved_transitions = 0;
+    }
+
+    /// Enregistre l'erreur de la dernière tentative
+    pub fn record_error(&mut self, error: String) {
+        if self.error_history.len() >= MAX_ERROR_HISTORY {
+            self.error_history.remove(0);
+        }
+        self.error_history.push(error.clone());
         self.error = Some(error);
-        self.retry_count += 1;
         self.updated_at = Utc::now();
-        if self.completed_at.is_none() {
-            self.completed_at = Some(Utc::now());
-        }
     }
 
     pub fn can_retry(&self) -> bool {
@@ -106,6 +416,46 @@ impl Task {
     pub fn should_retry(&self) -> bool {
         self.can_retry()
     }
+
+    /// Vrai si la tâche n'a plus lieu d'être commencée
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Vrai si un worker détient encore la tâche : attribuée et sous lease
+    /// valide (`lease_deadline` en ms epoch)
+    pub fn is_held_by_worker(&self, lease_deadline: Option<i64>, now: DateTime<Utc>) -> bool {
+        self.worker_id.is_some() && lease_deadline.is_some_and(|deadline| deadline >= now.timestamp_millis())
+    }
+
+    /// Statut d'attente d'une tâche qui reprend après une pause : différée ou
+    /// en retry tant que son échéance n'est pas passée, en queue sinon
+    pub fn resumed_status(&self, now: DateTime<Utc>) -> TaskStatus {
+        let due_at = self.next_retry_at.or(self.run_at).filter(|at| *at > now);
+        match (due_at, self.next_retry_at) {
+            (Some(_), Some(_)) => TaskStatus::Retrying,
+            (Some(_), None) => TaskStatus::Scheduled,
+            (None, _) => TaskStatus::Queued,
+        }
+    }
+
+    /// Durée au-delà de laquelle une tentative est interrompue
+    pub fn timeout(&self) -> Duration {
+        self.timeout_seconds
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| self.task_type.default_timeout())
+    }
+}
+
+impl TaskType {
+    /// Durée maximale d'une tentative quand la tâche n'en fixe pas
+    pub fn default_timeout(&self) -> Duration {
+        match self {
+            TaskType::VideoCompression => Duration::from_secs(3600),
+            TaskType::AudioProcessing => Duration::from_secs(900),
+            TaskType::ImageOptimization => Duration::from_secs(300),
+        }
+    }
 }
 
 impl std::fmt::Display for TaskType {
@@ -118,14 +468,44 @@ impl std::fmt::Display for TaskType {
     }
 }
 
+impl std::str::FromStr for TaskType {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "video" => Ok(TaskType::VideoCompression),
+            "audio" => Ok(TaskType::AudioProcessing),
+            "image" => Ok(TaskType::ImageOptimization),
+            _ => Err(format!("Invalid task type: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for TaskPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskPriority::High => write!(f, "high"),
+            TaskPriority::Normal => write!(f, "normal"),
+            TaskPriority::Low => write!(f, "low"),
+        }
+    }
+}
+
 impl std::fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TaskStatus::Pending => write!(f, "pending"),
+            TaskStatus::Scheduled => write!(f, "scheduled"),
+            TaskStatus::Blocked => write!(f, "blocked"),
+            TaskStatus::Queued => write!(f, "queued"),
             TaskStatus::Processing => write!(f, "processing"),
+            TaskStatus::Paused => write!(f, "paused"),
+            TaskStatus::Retrying => write!(f, "retrying"),
+            TaskStatus::Cancelling => write!(f, "cancelling"),
             TaskStatus::Completed => write!(f, "completed"),
             TaskStatus::Failed => write!(f, "failed"),
             TaskStatus::Cancelled => write!(f, "cancelled"),
+            TaskStatus::Expired => write!(f, "expired"),
         }
     }
 }
@@ -133,6 +513,9 @@ impl std::fmt::Display for TaskStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::media::MediaType;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
 
     #[test]
     fn test_task_creation() {
@@ -151,9 +534,32 @@ mod tests {
         assert_eq!(task.status, TaskStatus::Pending);
         assert_eq!(task.progress, 0.0);
         assert_eq!(task.retry_count, 0);
+        assert_eq!(task.priority, TaskPriority::Normal);
         assert!(task.error.is_none());
     }
 
+    #[test]
+    fn test_task_priority_defaults_when_missing() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Image,
+            file_path: PathBuf::from("/path/to/image.jpg"),
+            file_size: 2048,
+            original_name: "image.jpg".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let task = Task::new(TaskType::ImageOptimization, media).with_priority(TaskPriority::High);
+        let mut json = serde_json::to_value(&task).unwrap();
+        assert_eq!(json["priority"], "high");
+
+        // Les tâches créées avant l'ajout du champ restent lisibles
+        json.as_object_mut().unwrap().remove("priority");
+        let task: Task = serde_json::from_value(json).unwrap();
+        assert_eq!(task.priority, TaskPriority::Normal);
+    }
+
     #[test]
     fn test_task_status_update() {
         let media = MediaFile {
@@ -168,15 +574,102 @@ mod tests {
 
         let mut task = Task::new(TaskType::VideoCompression, media);
         
-        task.update_status(TaskStatus::Processing);
+        task.update_status(TaskStatus::Processing).unwrap();
         assert_eq!(task.status, TaskStatus::Processing);
         assert!(task.started_at.is_some());
 
-        task.update_status(TaskStatus::Completed);
+        task.update_status(TaskStatus::Completed).unwrap();
         assert_eq!(task.status, TaskStatus::Completed);
         assert!(task.completed_at.is_some());
     }
 
+    #[test]
+    fn test_task_status_rejects_illegal_transitions() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Audio,
+            file_path: PathBuf::from("/path/to/audio.mp3"),
+            file_size: 1024,
+            original_name: "audio.mp3".to_string(),
+            mime_type: "audio/mpeg".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let mut task = Task::new(TaskType::AudioProcessing, media);
+        task.update_status(TaskStatus::Queued).unwrap();
+        task.update_status(TaskStatus::Processing).unwrap();
+        task.update_status(TaskStatus::Cancelling).unwrap();
+
+        // Une annulation en cours ne peut pas repartir en retry
+        let err = task.update_status(TaskStatus::Retrying).unwrap_err();
+        assert_eq!(err.from, TaskStatus::Cancelling);
+        assert_eq!(err.to, TaskStatus::Retrying);
+        assert_eq!(task.status, TaskStatus::Cancelling);
+
+        task.update_status(TaskStatus::Cancelled).unwrap();
+        assert!(task.completed_at.is_some());
+
+        // Terminal
+        assert!(task.update_status(TaskStatus::Queued).is_err());
+        assert!(task.update_status(TaskStatus::Cancelled).is_ok());
+    }
+
+    #[test]
+    fn test_task_status_transition_table() {
+        assert!(TaskStatus::Failed.can_transition_to(&TaskStatus::Queued));
+        assert!(!TaskStatus::Completed.can_transition_to(&TaskStatus::Queued));
+        assert!(!TaskStatus::Queued.can_transition_to(&TaskStatus::Completed));
+        assert!(TaskStatus::Paused.can_transition_to(&TaskStatus::Processing));
+
+        let to_queued = TaskStatus::Queued.predecessors();
+        assert!(to_queued.contains(&TaskStatus::Retrying));
+        assert!(to_queued.contains(&TaskStatus::Failed));
+        assert!(!to_queued.contains(&TaskStatus::Cancelled));
+        assert!(TaskStatus::Pending.predecessors() == vec![TaskStatus::Pending]);
+
+        assert!(TaskStatus::Blocked.can_transition_to(&TaskStatus::Queued));
+        assert!(TaskStatus::Blocked.can_transition_to(&TaskStatus::Failed));
+        assert!(!TaskStatus::Blocked.can_transition_to(&TaskStatus::Processing));
+        assert!(!TaskStatus::Scheduled.can_transition_to(&TaskStatus::Blocked));
+
+        assert!(TaskStatus::Expired.is_terminal());
+        assert!(TaskStatus::Retrying.can_transition_to(&TaskStatus::Expired));
+        assert!(!TaskStatus::Processing.can_transition_to(&TaskStatus::Expired));
+        assert!(!TaskStatus::Expired.can_transition_to(&TaskStatus::Queued));
+    }
+
+    #[test]
+    fn test_task_status_reads_legacy_strings() {
+        let status: TaskStatus = serde_json::from_str("\"Cancelled\"").unwrap();
+        assert_eq!(status, TaskStatus::Cancelled);
+        let status: TaskStatus = serde_json::from_str("\"Cancelling\"").unwrap();
+        assert_eq!(status, TaskStatus::Cancelling);
+        assert_eq!(serde_json::to_string(&TaskStatus::Cancelling).unwrap(), "\"cancelling\"");
+    }
+
+    #[test]
+    fn test_task_scheduling() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Video,
+            file_path: PathBuf::from("/path/to/video.mp4"),
+            file_size: 1024000,
+            original_name: "video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let later = Utc::now() + chrono::Duration::minutes(10);
+        let task = Task::new(TaskType::VideoCompression, media.clone()).with_run_at(later);
+        assert_eq!(task.status, TaskStatus::Scheduled);
+        assert_eq!(task.run_at, Some(later));
+
+        // Une date passée n'a pas besoin d'attendre
+        let earlier = Utc::now() - chrono::Duration::minutes(10);
+        let task = Task::new(TaskType::VideoCompression, media).with_run_at(earlier);
+        assert_eq!(task.status, TaskStatus::Pending);
+    }
+
     #[test]
     fn test_task_progress_update() {
         let media = MediaFile {
@@ -218,13 +711,192 @@ mod tests {
         
         assert!(task.can_retry());
         
-        task.mark_failed("Test error".to_string());
+        task.increment_retry();
+        task.record_error("Test error".to_string());
         assert_eq!(task.retry_count, 1);
         assert!(task.can_retry());
 
-        task.mark_failed("Test error 2".to_string());
-        task.mark_failed("Test error 3".to_string());
+        for error in ["Test error 2", "Test error 3"] {
+            task.increment_retry();
+            task.record_error(error.to_string());
+        }
         assert_eq!(task.retry_count, 3);
         assert!(!task.can_retry());
     }
+
+    #[test]
+    fn test_error_history_is_bounded() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Video,
+            file_path: PathBuf::from("/path/to/video.mp4"),
+            file_size: 1024000,
+            original_name: "video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let mut task = Task::new(TaskType::VideoCompression, media);
+
+        for i in 0..(MAX_ERROR_HISTORY + 5) {
+            task.record_error(format!("error {}", i));
+        }
+
+        assert_eq!(task.error_history.len(), MAX_ERROR_HISTORY);
+        assert_eq!(task.error_history[0], "error 5");
+        assert_eq!(task.error.as_deref(), Some(format!("error {}", MAX_ERROR_HISTORY + 4).as_str()));
+    }
+
+    #[test]
+    fn test_status_history_records_transitions() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Audio,
+            file_path: PathBuf::from("/path/to/audio.mp3"),
+            file_size: 4096,
+            original_name: "audio.mp3".to_string(),
+            mime_type: "audio/mpeg".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let mut task = Task::new(TaskType::AudioProcessing, media);
+        task.update_status(TaskStatus::Queued).unwrap();
+        task.assign_worker("worker-1");
+        task.update_status(TaskStatus::Processing).unwrap();
+        task.update_status(TaskStatus::Processing).unwrap();
+        task.increment_retry();
+        task.record_error("boom".to_string());
+        task.update_status(TaskStatus::Retrying).unwrap();
+
+        assert_eq!(task.history.len(), 3);
+        assert_eq!(task.unsaved_transitions().len(), 3);
+
+        let retry = &task.history[2];
+        assert_eq!((&retry.from, &retry.to), (&TaskStatus::Processing, &TaskStatus::Retrying));
+        assert_eq!(retry.worker_id.as_deref(), Some("worker-1"));
+        assert_eq!(retry.attempt, 1);
+        assert_eq!(retry.error.as_deref(), Some("boom"));
+        assert!(task.worker_id.is_none());
+
+        task.mark_transitions_saved();
+        task.update_status(TaskStatus::Queued).unwrap();
+        assert_eq!(task.unsaved_transitions().len(), 1);
+
+        // L'historique est borné, les plus anciennes transitions sont oubliées
+        for _ in 0..MAX_STATUS_HISTORY {
+            task.update_status(TaskStatus::Paused).unwrap();
+            task.update_status(TaskStatus::Queued).unwrap();
+        }
+        assert_eq!(task.history.len(), MAX_STATUS_HISTORY);
+        assert_eq!(task.unsaved_transitions().len(), MAX_STATUS_HISTORY);
+        assert!(task.history.iter().all(|transition| transition.reason.is_none()));
+
+        // Interrompue par l'arrêt du worker : le motif est noté, même historique plein
+        task.update_status(TaskStatus::Processing).unwrap();
+        task.update_status_with_reason(TaskStatus::Queued, INTERRUPTED_BY_SHUTDOWN).unwrap();
+        let interrupted = task.history.last().unwrap();
+        assert_eq!(interrupted.to, TaskStatus::Queued);
+        assert_eq!(interrupted.reason.as_deref(), Some(INTERRUPTED_BY_SHUTDOWN));
+    }
+
+    #[test]
+    fn test_pause_resume_of_task_never_enqueued() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Image,
+            file_path: PathBuf::from("/path/to/image.png"),
+            file_size: 2048,
+            original_name: "image.png".to_string(),
+            mime_type: "image/png".to_string(),
+            metadata: HashMap::new(),
+        };
+        let now = Utc::now();
+
+        // Pausée avant que l'outbox l'ait enfilée : aucun worker ne la détient,
+        // la reprise la remet en queue au lieu d'attendre un worker
+        let mut task = Task::new(TaskType::ImageOptimization, media);
+        task.outbox = Some(OutboxEntry::new());
+        task.update_status(TaskStatus::Paused).unwrap();
+        assert!(!task.is_held_by_worker(None, now));
+        assert_eq!(task.resumed_status(now), TaskStatus::Queued);
+        task.update_status(task.resumed_status(now)).unwrap();
+
+        // Pausée pendant son traitement : détenue tant que la lease est valide
+        task.assign_worker("worker-1");
+        task.update_status(TaskStatus::Processing).unwrap();
+        task.update_status(TaskStatus::Paused).unwrap();
+        assert!(task.is_held_by_worker(Some(now.timestamp_millis() + 1000), now));
+        assert!(!task.is_held_by_worker(Some(now.timestamp_millis() - 1000), now));
+
+        // Pausée en attente de retry : reprend son attente
+        task.next_retry_at = Some(now + chrono::Duration::minutes(1));
+        assert_eq!(task.resumed_status(now), TaskStatus::Retrying);
+    }
+
+    #[test]
+    fn test_idempotency_key_omitted_when_absent() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Video,
+            file_path: PathBuf::from("/path/to/video.mp4"),
+            file_size: 1024000,
+            original_name: "video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        // L'index unique partiel ne couvre que les tâches qui portent une clé
+        let mut task = Task::new(TaskType::VideoCompression, media);
+        let json = serde_json::to_value(&task).unwrap();
+        assert!(json.get("idempotency_key").is_none());
+
+        task.idempotency_key = Some("retry-42".to_string());
+        let json = serde_json::to_value(&task).unwrap();
+        assert_eq!(json["idempotency_key"], "retry-42");
+    }
+
+    #[test]
+    fn test_timeout_defaults_to_task_type() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Image,
+            file_path: PathBuf::from("/path/to/photo.jpg"),
+            file_size: 2048,
+            original_name: "photo.jpg".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let mut task = Task::new(TaskType::ImageOptimization, media);
+        assert_eq!(task.timeout(), TaskType::ImageOptimization.default_timeout());
+        assert!(serde_json::to_value(&task).unwrap().get("timeout_seconds").is_none());
+
+        task.timeout_seconds = Some(42);
+        assert_eq!(task.timeout(), Duration::from_secs(42));
+    }
+
+    #[test]
+    fn test_task_expiry() {
+        let media = MediaFile {
+            file_id: "test-123".to_string(),
+            file_type: MediaType::Image,
+            file_path: PathBuf::from("/path/to/preview.jpg"),
+            file_size: 2048,
+            original_name: "preview.jpg".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            metadata: HashMap::new(),
+        };
+
+        let now = Utc::now();
+        let mut task = Task::new(TaskType::ImageOptimization, media);
+        assert!(!task.is_expired(now));
+
+        task.expires_at = Some(now + chrono::Duration::minutes(10));
+        assert!(!task.is_expired(now));
+        assert!(task.is_expired(now + chrono::Duration::minutes(10)));
+
+        task.update_status(TaskStatus::Queued).unwrap();
+        task.update_status(TaskStatus::Expired).unwrap();
+        assert!(task.completed_at.is_some());
+    }
 }
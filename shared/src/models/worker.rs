@@ -0,0 +1,96 @@
+use crate::models::task::TaskType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Nombre de heartbeats manqués au-delà duquel un worker est considéré mort
+pub const MISSED_HEARTBEATS_BEFORE_DEAD: u32 = 3;
+
+/// Fiche d'un worker dans le registre, rafraîchie à chaque heartbeat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerInfo {
+    pub worker_id: String,
+    /// Types de tâches servis par le worker
+    pub task_types: Vec<TaskType>,
+    pub hostname: String,
+    pub version: String,
+    pub started_at: DateTime<Utc>,
+    pub last_heartbeat: DateTime<Utc>,
+    /// Période des heartbeats, permet aux lecteurs de juger la liveness
+    pub heartbeat_interval_secs: u64,
+    /// Nombre maximal de tâches traitées en parallèle
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Tâches en cours de traitement
+    #[serde(default)]
+    pub current_task_ids: Vec<String>,
+    /// Tâches terminées avec succès depuis le démarrage
+    pub processed_count: u64,
+    /// Tentatives en échec depuis le démarrage
+    pub failed_count: u64,
+}
+
+fn default_concurrency() -> usize {
+    1
+}
+
+impl WorkerInfo {
+    pub fn new(
+        worker_id: String,
+        task_types: Vec<TaskType>,
+        hostname: String,
+        version: String,
+        heartbeat_interval_secs: u64,
+        concurrency: usize,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            worker_id,
+            task_types,
+            hostname,
+            version,
+            started_at: now,
+            last_heartbeat: now,
+            heartbeat_interval_secs,
+            concurrency,
+            current_task_ids: Vec::new(),
+            processed_count: 0,
+            failed_count: 0,
+        }
+    }
+
+    /// Un worker est vivant tant qu'il n'a pas manqué plusieurs heartbeats d'affilée
+    pub fn is_alive_at(&self, now: DateTime<Utc>) -> bool {
+        let tolerance = self.heartbeat_interval_secs.max(1) * MISSED_HEARTBEATS_BEFORE_DEAD as u64;
+        now.signed_duration_since(self.last_heartbeat).num_seconds() < tolerance as i64
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.is_alive_at(Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_liveness() {
+        let mut worker = WorkerInfo::new(
+            "worker-1".to_string(),
+            vec![TaskType::VideoCompression],
+            "host-a".to_string(),
+            "0.1.0".to_string(),
+            5,
+            1,
+        );
+
+        assert!(worker.is_alive());
+
+        let now = worker.last_heartbeat;
+        assert!(worker.is_alive_at(now + chrono::Duration::seconds(14)));
+        assert!(!worker.is_alive_at(now + chrono::Duration::seconds(15)));
+
+        worker.last_heartbeat = Utc::now() - chrono::Duration::minutes(5);
+        assert!(!worker.is_alive());
+    }
+}
@@ -0,0 +1,350 @@
+//! Outbox des enqueues. Le marqueur `outbox` est écrit avec la tâche, dans le
+//! même document MongoDB, et retiré une fois la tâche enfilée : une tâche
+//! insérée dont l'enqueue Redis a échoué est retrouvée et enfilée par le relais.
+
+use crate::dependencies::{self, DependencyError};
+use crate::models::{Task, TaskStatus};
+use crate::queue;
+use crate::retry::RetryPolicy;
+use chrono::{DateTime, Utc};
+use mongodb::bson::{self, doc};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use redis::aio::ConnectionLike;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Nombre maximal de tâches traitées par passe du relais
+const RELAY_BATCH_SIZE: i64 = 100;
+
+/// Nombre d'éléments lus par commande lors d'un relevé des queues Redis
+const SNAPSHOT_PAGE: isize = 1000;
+
+/// Enqueue d'une tâche en attente de confirmation par Redis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub created_at: DateTime<Utc>,
+    /// Tentatives de relais en échec
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+/// Erreurs du relais et de la réconciliation
+#[derive(Debug, thiserror::Error)]
+pub enum OutboxError {
+    #[error("MongoDB error: {0}")]
+    Mongo(#[from] mongodb::error::Error),
+
+    #[error("BSON serialization error: {0}")]
+    Bson(#[from] bson::ser::Error),
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error(transparent)]
+    Dependency(#[from] DependencyError),
+}
+
+impl OutboxEntry {
+    pub fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            created_at: now,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+        }
+    }
+
+    /// Vrai si le relais doit traiter l'entrée. Une entrée plus jeune que
+    /// `min_age` est laissée à la requête qui l'a écrite.
+    pub fn is_due(&self, now: DateTime<Utc>, min_age: Duration) -> bool {
+        let min_age = chrono::Duration::from_std(min_age).unwrap_or(chrono::Duration::zero());
+        self.created_at + min_age <= now && self.next_attempt_at <= now
+    }
+
+    /// Enregistre un échec de relais et repousse la prochaine tentative
+    pub fn record_failure(&mut self, error: String, policy: &RetryPolicy, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_error = Some(error);
+        let delay = chrono::Duration::from_std(policy.next_delay(self.attempts))
+            .unwrap_or(chrono::Duration::zero());
+        self.next_attempt_at = now + delay;
+    }
+}
+
+impl Default for OutboxEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Statuts d'une tâche qui doit se trouver dans une queue Redis
+fn is_waiting(status: &TaskStatus) -> bool {
+    matches!(
+        status,
+        TaskStatus::Pending | TaskStatus::Queued | TaskStatus::Scheduled | TaskStatus::Retrying
+    )
+}
+
+/// Retire le marqueur d'outbox de tâches enfilées
+pub async fn clear(db: &Database, task_ids: &[String]) -> Result<(), mongodb::error::Error> {
+    db.collection::<Task>("tasks")
+        .update_many(
+            doc! { "task_id": { "$in": task_ids }, "outbox": { "$exists": true } },
+            doc! { "$unset": { "outbox": "" } },
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Enfile une tâche en attente si elle n'est nulle part dans Redis ; renvoie
+/// vrai si elle a été enfilée
+pub async fn enqueue_if_absent<C: ConnectionLike + Send>(
+    conn: &mut C,
+    task: &Task,
+) -> Result<bool, redis::RedisError> {
+    // Une tâche différée dont l'échéance est passée va directement dans sa lane
+    let due_at = match task.status {
+        TaskStatus::Scheduled => task.run_at,
+        TaskStatus::Retrying => task.next_retry_at,
+        _ => None,
+    }
+    .filter(|at| *at > Utc::now());
+
+    let enqueued: i32 = queue::enqueue_if_absent_script()
+        .key(queue::lane_key(&task.task_type, task.priority))
+        .key(queue::notify_key(&task.task_type))
+        .key(queue::scheduled_key(&task.task_type))
+        .key(queue::paused_key(&task.task_type))
+        .key(queue::LEASES_KEY)
+        .arg(&task.id)
+        .arg(due_at.map(|at| at.timestamp_millis().to_string()).unwrap_or_default())
+        .invoke_async(conn)
+        .await?;
+
+    Ok(enqueued > 0)
+}
+
+/// Enfile une tâche marquée d'après son statut stocké. Une tâche déjà sortie
+/// de l'attente n'a rien à enfiler.
+async fn publish<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    task: &Task,
+) -> Result<(), OutboxError> {
+    if task.status == TaskStatus::Blocked {
+        // Les dépendances ont pu se terminer avant l'insertion
+        dependencies::release_if_ready(db, conn, &task.id).await?;
+    } else if is_waiting(&task.status) && enqueue_if_absent(conn, task).await? {
+        tracing::info!(task_id = %task.id, "Task enqueued by outbox relay");
+    }
+
+    Ok(())
+}
+
+/// Relaie les tâches dont le marqueur d'outbox est arrivé à échéance ; renvoie
+/// le nombre de tâches réglées. Un relais en échec est retenté avec backoff.
+pub async fn relay_pending<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    policy: &RetryPolicy,
+    min_age: Duration,
+) -> Result<usize, OutboxError> {
+    let collection = db.collection::<Task>("tasks");
+    let now = Utc::now();
+
+    let filter = doc! { "outbox.next_attempt_at": { "$lte": bson::to_bson(&now)? } };
+    let options = FindOptions::builder()
+        .sort(doc! { "outbox.created_at": 1 })
+        .limit(RELAY_BATCH_SIZE)
+        .build();
+
+    let mut tasks = Vec::new();
+    let mut cursor = collection.find(filter, options).await?;
+    while cursor.advance().await? {
+        tasks.push(cursor.deserialize_current()?);
+    }
+
+    let mut relayed = 0;
+    for task in tasks {
+        // Les dates sont comparées sous forme de texte : on revérifie l'échéance
+        let Some(mut entry) = task.outbox.clone().filter(|entry| entry.is_due(now, min_age)) else {
+            continue;
+        };
+
+        match publish(db, conn, &task).await {
+            Ok(()) => {
+                clear(db, std::slice::from_ref(&task.id)).await?;
+                relayed += 1;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    task_id = %task.id,
+                    attempts = entry.attempts + 1,
+                    error = %e,
+                    "Outbox relay failed, will retry"
+                );
+                entry.record_failure(e.to_string(), policy, now);
+                let update = doc! {
+                    "$set": {
+                        "outbox.attempts": entry.attempts,
+                        "outbox.next_attempt_at": bson::to_bson(&entry.next_attempt_at)?,
+                        "outbox.last_error": &entry.last_error,
+                    }
+                };
+                collection
+                    .update_one(doc! { "task_id": &task.id, "outbox": { "$exists": true } }, update, None)
+                    .await?;
+            }
+        }
+    }
+
+    Ok(relayed)
+}
+
+/// Relevé des identifiants présents dans Redis (lanes, différées, en pause,
+/// sous lease), chargé une fois par passe et par type de tâche
+struct QueueSnapshot {
+    loaded_types: HashSet<String>,
+    task_ids: HashSet<String>,
+}
+
+impl QueueSnapshot {
+    async fn load<C: ConnectionLike + Send>(conn: &mut C) -> Result<Self, redis::RedisError> {
+        let mut snapshot = Self {
+            loaded_types: HashSet::new(),
+            task_ids: HashSet::new(),
+        };
+        snapshot.scan_pairs(conn, "ZSCAN", queue::LEASES_KEY).await?;
+        Ok(snapshot)
+    }
+
+    /// Vrai si la tâche figure dans le relevé ; les queues de son type sont
+    /// relevées à la première tâche de ce type
+    async fn contains<C: ConnectionLike + Send>(&mut self, conn: &mut C, task: &Task) -> Result<bool, redis::RedisError> {
+        if self.loaded_types.insert(task.task_type.to_string()) {
+            for lane in queue::lane_keys(&task.task_type) {
+                self.read_list(conn, &lane).await?;
+            }
+            self.scan_pairs(conn, "ZSCAN", &queue::scheduled_key(&task.task_type)).await?;
+            self.scan_pairs(conn, "HSCAN", &queue::paused_key(&task.task_type)).await?;
+        }
+
+        Ok(self.task_ids.contains(&task.id))
+    }
+
+    /// Lit une liste par pages : Redis n'est jamais bloqué sur toute la lane
+    async fn read_list<C: ConnectionLike + Send>(&mut self, conn: &mut C, key: &str) -> Result<(), redis::RedisError> {
+        let mut start = 0;
+        loop {
+            let page: Vec<String> = redis::cmd("LRANGE")
+                .arg(key)
+                .arg(start)
+                .arg(start + SNAPSHOT_PAGE - 1)
+                .query_async(conn)
+                .await?;
+            let len = page.len() as isize;
+            self.task_ids.extend(page);
+            if len < SNAPSHOT_PAGE {
+                return Ok(());
+            }
+            start += SNAPSHOT_PAGE;
+        }
+    }
+
+    /// Parcourt un sorted set ou un hash avec `ZSCAN` / `HSCAN` et garde les
+    /// membres (un élément sur deux, l'autre étant le score ou la valeur)
+    async fn scan_pairs<C: ConnectionLike + Send>(
+        &mut self,
+        conn: &mut C,
+        command: &str,
+        key: &str,
+    ) -> Result<(), redis::RedisError> {
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, items): (u64, Vec<String>) = redis::cmd(command)
+                .arg(key)
+                .arg(cursor)
+                .arg("COUNT")
+                .arg(SNAPSHOT_PAGE)
+                .query_async(conn)
+                .await?;
+            self.task_ids.extend(items.into_iter().step_by(2));
+            if next == 0 {
+                return Ok(());
+            }
+            cursor = next;
+        }
+    }
+}
+
+/// Ré-enfile les tâches en attente dans MongoDB qui ne sont dans aucune queue
+/// Redis (enqueue perdu, Redis vidé...). Seules les tâches inchangées depuis
+/// `min_age` sont examinées, pour ne pas devancer un worker ou le relais.
+///
+/// Les queues sont relevées une fois par passe ; seules les tâches absentes
+/// du relevé sont revérifiées, atomiquement, avant d'être enfilées.
+pub async fn reconcile<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    min_age: Duration,
+) -> Result<usize, OutboxError> {
+    let collection = db.collection::<Task>("tasks");
+    let cutoff = Utc::now() - chrono::Duration::from_std(min_age).unwrap_or(chrono::Duration::zero());
+
+    let waiting: Vec<String> = TaskStatus::ALL
+        .iter()
+        .filter(|status| is_waiting(status))
+        .map(|status| status.to_string())
+        .collect();
+    // Les tâches encore marquées sont du ressort du relais
+    let filter = doc! {
+        "status": { "$in": waiting },
+        "updated_at": { "$lte": bson::to_bson(&cutoff)? },
+        "outbox": { "$exists": false },
+    };
+
+    let mut snapshot = QueueSnapshot::load(conn).await?;
+    let mut reconciled = 0;
+    let mut cursor = collection.find(filter, None).await?;
+    while cursor.advance().await? {
+        let task = cursor.deserialize_current()?;
+        if task.updated_at > cutoff || snapshot.contains(conn, &task).await? {
+            continue;
+        }
+
+        if enqueue_if_absent(conn, &task).await? {
+            tracing::warn!(task_id = %task.id, status = %task.status, "Task missing from Redis, re-enqueued");
+            reconciled += 1;
+        }
+    }
+
+    Ok(reconciled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outbox_entry_is_due() {
+        let mut entry = OutboxEntry::new();
+        let min_age = Duration::from_secs(10);
+
+        // Laissée à la requête qui vient de l'écrire
+        assert!(!entry.is_due(entry.created_at, min_age));
+        assert!(entry.is_due(entry.created_at + chrono::Duration::seconds(10), min_age));
+
+        // Repoussée après un échec
+        let policy = RetryPolicy::default();
+        let failed_at = entry.created_at + chrono::Duration::seconds(10);
+        entry.record_failure("connection refused".to_string(), &policy, failed_at);
+        assert_eq!(entry.attempts, 1);
+        assert!(!entry.is_due(failed_at, min_age));
+        assert!(entry.is_due(failed_at + chrono::Duration::from_std(policy.backoff(1)).unwrap(), min_age));
+    }
+}
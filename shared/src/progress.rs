@@ -0,0 +1,75 @@
+/// Sélection des valeurs de progression à diffuser : un processor peut en
+/// remonter des centaines, seules celles qui avancent assez sont publiées et
+/// seuls les paliers (checkpoints) sont écrits dans MongoDB.
+///
+/// La limite de fréquence est appliquée par l'appelant, qui ne présente ici
+/// que la dernière valeur reçue.
+#[derive(Debug, Clone)]
+pub struct ProgressThrottle {
+    /// Avancée minimale entre deux publications
+    min_step: f32,
+    /// Pas des checkpoints écrits dans MongoDB
+    checkpoint_step: f32,
+    last_published: Option<f32>,
+    last_checkpoint: f32,
+}
+
+impl ProgressThrottle {
+    pub fn new(min_step: f32, checkpoint_step: f32, initial: f32) -> Self {
+        Self {
+            min_step,
+            checkpoint_step,
+            last_published: None,
+            last_checkpoint: initial,
+        }
+    }
+
+    /// Vrai si la valeur doit être publiée ; la fin (1.0) l'est toujours
+    pub fn should_publish(&mut self, progress: f32) -> bool {
+        let publish = match self.last_published {
+            None => true,
+            Some(last) if progress >= 1.0 => last < 1.0,
+            Some(last) => (progress - last).abs() >= self.min_step,
+        };
+        if publish {
+            self.last_published = Some(progress);
+        }
+        publish
+    }
+
+    /// Vrai si la valeur franchit un palier et doit être écrite dans MongoDB
+    pub fn is_checkpoint(&mut self, progress: f32) -> bool {
+        let crossed = if progress >= 1.0 {
+            self.last_checkpoint < 1.0
+        } else {
+            let step = self.checkpoint_step.max(f32::EPSILON);
+            (progress / step).floor() > (self.last_checkpoint / step).floor()
+        };
+        if crossed {
+            self.last_checkpoint = progress;
+        }
+        crossed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_throttle() {
+        let mut throttle = ProgressThrottle::new(0.01, 0.25, 0.0);
+
+        let published: Vec<f32> = [0.0, 0.005, 0.01, 0.015, 0.5, 0.999, 1.0, 1.0]
+            .into_iter()
+            .filter(|progress| throttle.should_publish(*progress))
+            .collect();
+        assert_eq!(published, vec![0.0, 0.01, 0.5, 0.999, 1.0]);
+
+        let checkpoints: Vec<f32> = (0..=100)
+            .map(|i| i as f32 / 100.0)
+            .filter(|progress| throttle.is_checkpoint(*progress))
+            .collect();
+        assert_eq!(checkpoints, vec![0.25, 0.5, 0.75, 1.0]);
+    }
+}
@@ -1,27 +1,45 @@
+use crate::models::{Task, TaskType};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt};
+use redis::aio::ConnectionManager;
 use redis::Client as RedisClient;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 
-/// Client pour gérer les publications/souscriptions Redis
+/// Client pour gérer les publications/souscriptions Redis. Les publications
+/// passent par une connexion unique, ouverte au premier besoin et partagée
+/// entre les clones du client.
 #[derive(Clone)]
 pub struct PubSubClient {
     redis_client: RedisClient,
+    conn: Arc<OnceCell<ConnectionManager>>,
 }
 
 impl PubSubClient {
     /// Crée un nouveau client pub/sub
     pub fn new(redis_client: RedisClient) -> Self {
-        Self { redis_client }
+        Self {
+            redis_client,
+            conn: Arc::new(OnceCell::new()),
+        }
     }
     
-    /// Publie un message sur un canal
-    pub async fn publish(&self, channel: &str, message: &str) -> Result<()> {
-        let mut conn = self.redis_client
-            .get_async_connection()
+    /// Connexion de publication, reconnectée automatiquement en cas de coupure
+    async fn connection(&self) -> Result<ConnectionManager> {
+        let conn = self.conn
+            .get_or_try_init(|| self.redis_client.get_connection_manager())
             .await
             .context("Failed to get Redis connection for publish")?;
+        Ok(conn.clone())
+    }
+    
+    /// Publie un message sur un canal
+    pub async fn publish(&self, channel: &str, message: &str) -> Result<()> {
+        let mut conn = self.connection().await?;
         
-        redis::cmd("PUBLISH")
+        let _: i64 = redis::cmd("PUBLISH")
             .arg(channel)
             .arg(message)
             .query_async(&mut conn)
@@ -39,21 +57,68 @@ impl PubSubClient {
         self.publish(channel, &message).await
     }
     
+    /// Envoie une commande de pause au worker qui traite la tâche
+    pub async fn pause_task(&self, task_id: &str) -> Result<()> {
+        let command = TaskCommand::Pause { task_id: task_id.to_string() };
+        self.publish_command(&control_channel(task_id), &command).await
+    }
+    
+    /// Envoie une commande de reprise au worker qui traite la tâche
+    pub async fn resume_task(&self, task_id: &str) -> Result<()> {
+        let command = TaskCommand::Resume { task_id: task_id.to_string() };
+        self.publish_command(&control_channel(task_id), &command).await
+    }
+    
+    /// Publie un événement sur le canal global ; la progression l'est aussi
+    /// sur le canal de la tâche
+    pub async fn publish_event(&self, event: &TaskEvent) -> Result<()> {
+        self.publish_events(std::slice::from_ref(event)).await?;
+        
+        tracing::debug!(task_id = %event.task_id(), event = %event.kind(), "Published task event");
+        Ok(())
+    }
+    
+    /// Publie plusieurs événements en un seul pipeline
+    pub async fn publish_events(&self, events: &[TaskEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        
+        let mut pipe = redis::pipe();
+        for event in events {
+            let message = event.to_json().context("Failed to serialize task event")?;
+            pipe.cmd("PUBLISH").arg(EVENTS_CHANNEL).arg(&message).ignore();
+            if let TaskEvent::Progress { ref task_id, .. } = event {
+                pipe.cmd("PUBLISH").arg(progress_channel(task_id)).arg(&message).ignore();
+            }
+        }
+        
+        let mut conn = self.connection().await?;
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .context("Failed to publish task events to Redis")?;
+        
+        Ok(())
+    }
+    
     /// Publie une commande d'annulation pour une tâche spécifique
     pub async fn cancel_task(&self, task_id: &str) -> Result<()> {
-        let channel = format!("task:cancel:{}", task_id);
-        self.publish(&channel, "cancel").await
+        self.publish(&cancel_channel(task_id), "cancel").await
     }
     
     /// Souscrit à un pattern de canaux (psubscribe)
     pub async fn psubscribe(&self, patterns: Vec<String>) -> Result<redis::aio::PubSub> {
-        let pubsub = self.redis_client
-            .get_async_connection()
+        let mut pubsub = self.redis_client
+            .get_async_pubsub()
             .await
-            .context("Failed to get Redis connection for psubscribe")?
-            .into_pubsub();
+            .context("Failed to get Redis connection for psubscribe")?;
         
         for pattern in &patterns {
+            pubsub
+                .psubscribe(pattern)
+                .await
+                .with_context(|| format!("Failed to psubscribe to {}", pattern))?;
             tracing::debug!(pattern = %pattern, "Pattern subscribed to Redis");
         }
         
@@ -62,18 +127,260 @@ impl PubSubClient {
     
     /// Souscrit à un ou plusieurs canaux exacts
     pub async fn subscribe(&self, channels: Vec<String>) -> Result<redis::aio::PubSub> {
-        let pubsub = self.redis_client
-            .get_async_connection()
+        let mut pubsub = self.redis_client
+            .get_async_pubsub()
             .await
-            .context("Failed to get Redis connection for subscribe")?
-            .into_pubsub();
+            .context("Failed to get Redis connection for subscribe")?;
         
         for channel in &channels {
+            pubsub
+                .subscribe(channel)
+                .await
+                .with_context(|| format!("Failed to subscribe to {}", channel))?;
             tracing::debug!(channel = %channel, "Subscribed to Redis channel");
         }
         
         Ok(pubsub)
     }
+    
+    /// Souscrit à des canaux d'événements ; les messages illisibles ou d'une
+    /// version inconnue sont ignorés
+    pub async fn subscribe_events(&self, channels: Vec<String>) -> Result<impl Stream<Item = TaskEvent> + Send> {
+        let pubsub = self.subscribe(channels).await?;
+        
+        Ok(pubsub.into_on_message().filter_map(|msg| async move {
+            let payload: String = msg.get_payload().ok()?;
+            match TaskEvent::from_json(&payload) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    tracing::warn!(channel = %msg.get_channel_name(), error = %e, "Ignoring unreadable task event");
+                    None
+                }
+            }
+        }))
+    }
+}
+
+/// Pattern des canaux de commandes (`TaskCommand` JSON), écouté par les workers
+pub const CONTROL_CHANNEL_PATTERN: &str = "task:control:*";
+
+/// Canal global des événements de toutes les tâches
+pub const EVENTS_CHANNEL: &str = "task:events";
+
+/// Canal de progression d'une tâche
+pub fn progress_channel(task_id: &str) -> String {
+    format!("task:progress:{}", task_id)
+}
+
+/// Canal d'annulation d'une tâche
+pub fn cancel_channel(task_id: &str) -> String {
+    format!("task:cancel:{}", task_id)
+}
+
+/// Canal de commandes d'une tâche
+pub fn control_channel(task_id: &str) -> String {
+    format!("task:control:{}", task_id)
+}
+
+/// Version du format JSON des événements, incrémentée à chaque changement incompatible
+pub const EVENT_VERSION: u32 = 1;
+
+/// Événement du cycle de vie d'une tâche, publié en JSON versionné :
+/// `{"version": 1, "type": "progress", "task_id": ..., ...}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskEvent {
+    /// Tâche créée via l'API
+    Created {
+        task_id: String,
+        task_type: TaskType,
+        at: DateTime<Utc>,
+    },
+    
+    /// Tâche (re)mise dans sa lane
+    Queued {
+        task_id: String,
+        /// Motif d'une remise en attente après interruption (`"shutdown"`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        interrupted: Option<String>,
+        at: DateTime<Utc>,
+    },
+    
+    /// Tentative commencée par un worker
+    Started {
+        task_id: String,
+        worker_id: String,
+        attempt: u32,
+        at: DateTime<Utc>,
+    },
+    
+    /// Progression de la tentative en cours, entre 0.0 et 1.0
+    Progress {
+        task_id: String,
+        progress: f32,
+        at: DateTime<Utc>,
+    },
+    
+    Completed { task_id: String, at: DateTime<Utc> },
+    
+    /// Échec définitif (retries épuisés ou dépendance en échec)
+    Failed {
+        task_id: String,
+        error: Option<String>,
+        at: DateTime<Utc>,
+    },
+    
+    Cancelled { task_id: String, at: DateTime<Utc> },
+    
+    /// Tâche abandonnée faute d'avoir commencé avant son `expires_at`
+    Expired { task_id: String, at: DateTime<Utc> },
+    
+    /// Tentative en échec, nouvelle tentative programmée
+    Retried {
+        task_id: String,
+        attempt: u32,
+        retry_at: Option<DateTime<Utc>>,
+        error: Option<String>,
+        at: DateTime<Utc>,
+    },
+}
+
+/// Enveloppe JSON d'un événement : la version à côté des champs de l'événement
+#[derive(Serialize, Deserialize)]
+struct EventEnvelope<E> {
+    version: u32,
+    #[serde(flatten)]
+    event: E,
+}
+
+/// Erreurs de lecture d'un événement
+#[derive(Debug, thiserror::Error)]
+pub enum EventError {
+    #[error("Unsupported event version {0}")]
+    UnsupportedVersion(u32),
+    
+    #[error("Invalid event: {0}")]
+    Invalid(#[from] serde_json::Error),
+}
+
+impl TaskEvent {
+    pub fn created(task: &Task) -> Self {
+        TaskEvent::Created {
+            task_id: task.id.clone(),
+            task_type: task.task_type.clone(),
+            at: Utc::now(),
+        }
+    }
+    
+    pub fn queued(task_id: &str) -> Self {
+        TaskEvent::Queued { task_id: task_id.to_string(), interrupted: None, at: Utc::now() }
+    }
+    
+    /// Tâche remise en attente après avoir été interrompue
+    pub fn interrupted(task_id: &str, reason: &str) -> Self {
+        TaskEvent::Queued {
+            task_id: task_id.to_string(),
+            interrupted: Some(reason.to_string()),
+            at: Utc::now(),
+        }
+    }
+    
+    pub fn started(task: &Task, worker_id: &str) -> Self {
+        TaskEvent::Started {
+            task_id: task.id.clone(),
+            worker_id: worker_id.to_string(),
+            attempt: task.retry_count + 1,
+            at: Utc::now(),
+        }
+    }
+    
+    pub fn progress(task_id: &str, progress: f32) -> Self {
+        TaskEvent::Progress {
+            task_id: task_id.to_string(),
+            progress,
+            at: Utc::now(),
+        }
+    }
+    
+    pub fn completed(task_id: &str) -> Self {
+        TaskEvent::Completed { task_id: task_id.to_string(), at: Utc::now() }
+    }
+    
+    pub fn failed(task: &Task) -> Self {
+        TaskEvent::Failed {
+            task_id: task.id.clone(),
+            error: task.error.clone(),
+            at: Utc::now(),
+        }
+    }
+    
+    pub fn cancelled(task_id: &str) -> Self {
+        TaskEvent::Cancelled { task_id: task_id.to_string(), at: Utc::now() }
+    }
+    
+    pub fn expired(task_id: &str) -> Self {
+        TaskEvent::Expired { task_id: task_id.to_string(), at: Utc::now() }
+    }
+    
+    pub fn retried(task: &Task) -> Self {
+        TaskEvent::Retried {
+            task_id: task.id.clone(),
+            attempt: task.retry_count,
+            retry_at: task.next_retry_at,
+            error: task.error.clone(),
+            at: Utc::now(),
+        }
+    }
+    
+    pub fn task_id(&self) -> &str {
+        match self {
+            TaskEvent::Created { task_id, .. }
+            | TaskEvent::Queued { task_id, .. }
+            | TaskEvent::Started { task_id, .. }
+            | TaskEvent::Progress { task_id, .. }
+            | TaskEvent::Completed { task_id, .. }
+            | TaskEvent::Failed { task_id, .. }
+            | TaskEvent::Cancelled { task_id, .. }
+            | TaskEvent::Expired { task_id, .. }
+            | TaskEvent::Retried { task_id, .. } => task_id,
+        }
+    }
+    
+    /// Nom de l'événement, tel que sérialisé dans `type`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TaskEvent::Created { .. } => "created",
+            TaskEvent::Queued { .. } => "queued",
+            TaskEvent::Started { .. } => "started",
+            TaskEvent::Progress { .. } => "progress",
+            TaskEvent::Completed { .. } => "completed",
+            TaskEvent::Failed { .. } => "failed",
+            TaskEvent::Cancelled { .. } => "cancelled",
+            TaskEvent::Expired { .. } => "expired",
+            TaskEvent::Retried { .. } => "retried",
+        }
+    }
+    
+    /// Sérialise l'événement dans la version courante du format
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&EventEnvelope { version: EVENT_VERSION, event: self })
+    }
+    
+    /// Lit un événement ; une version plus récente que celle connue est refusée
+    pub fn from_json(json: &str) -> Result<Self, EventError> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        
+        let Version { version } = serde_json::from_str(json)?;
+        if version != EVENT_VERSION {
+            return Err(EventError::UnsupportedVersion(version));
+        }
+        
+        let envelope: EventEnvelope<TaskEvent> = serde_json::from_str(json)?;
+        Ok(envelope.event)
+    }
 }
 
 /// Commandes possibles pour les tâches
@@ -83,12 +390,10 @@ pub enum TaskCommand {
     /// Annuler une tâche
     Cancel { task_id: String },
     
-    /// Mettre en pause une tâche (future implémentation)
-    #[allow(dead_code)]
+    /// Suspendre une tâche en cours
     Pause { task_id: String },
     
-    /// Reprendre une tâche (future implémentation)
-    #[allow(dead_code)]
+    /// Reprendre une tâche suspendue
     Resume { task_id: String },
 }
 
@@ -102,3 +407,35 @@ impl TaskCommand {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_event_json() {
+        let event = TaskEvent::progress("task-1", 0.42);
+        let json = event.to_json().unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], EVENT_VERSION);
+        assert_eq!(value["type"], "progress");
+        assert_eq!(value["task_id"], "task-1");
+
+        assert_eq!(TaskEvent::from_json(&json).unwrap(), event);
+
+        // Le motif d'interruption n'apparaît que s'il est renseigné
+        let value: serde_json::Value = serde_json::from_str(&TaskEvent::queued("task-1").to_json().unwrap()).unwrap();
+        assert!(value.get("interrupted").is_none());
+        let interrupted = TaskEvent::interrupted("task-1", "shutdown");
+        assert_eq!(TaskEvent::from_json(&interrupted.to_json().unwrap()).unwrap(), interrupted);
+
+        let expired = TaskEvent::expired("task-1");
+        assert_eq!(expired.kind(), "expired");
+        assert_eq!(TaskEvent::from_json(&expired.to_json().unwrap()).unwrap(), expired);
+
+        // Une version inconnue est refusée plutôt que mal interprétée
+        let future = json.replacen("\"version\":1", "\"version\":2", 1);
+        assert!(matches!(TaskEvent::from_json(&future), Err(EventError::UnsupportedVersion(2))));
+    }
+}
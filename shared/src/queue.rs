@@ -0,0 +1,495 @@
+//! Queues Redis. Elles ne contiennent que des task_id : MongoDB fait foi pour
+//! le contenu et le statut des tâches.
+
+use crate::models::{Task, TaskPriority, TaskType};
+use redis::Script;
+
+/// Sorted set des leases : membre = task_id, score = échéance (ms epoch)
+pub const LEASES_KEY: &str = "queue:leases";
+
+/// Set des listes de traitement connues (une par worker)
+pub const PROCESSING_LISTS_KEY: &str = "queue:processing_lists";
+
+/// Registre des workers : champ = worker_id, valeur = `WorkerInfo` JSON
+pub const WORKERS_KEY: &str = "queue:workers";
+
+/// Poids des lanes sur un cycle de dequeue (high, normal, low).
+///
+/// Une lane passe en tête de l'ordre de dequeue pour autant de slots que
+/// son poids : les lanes basses avancent même sous une charge haute constante.
+pub const LANE_WEIGHTS: [(TaskPriority, u64); 3] = [
+    (TaskPriority::High, 6),
+    (TaskPriority::Normal, 3),
+    (TaskPriority::Low, 1),
+];
+
+/// Liste des task_id d'un type de tâche pour une priorité donnée.
+///
+/// La lane normale garde le nom historique `queue:{type}`.
+pub fn lane_key(task_type: &TaskType, priority: TaskPriority) -> String {
+    match priority {
+        TaskPriority::Normal => format!("queue:{}", task_type),
+        _ => format!("queue:{}:{}", task_type, priority),
+    }
+}
+
+/// Lanes d'un type de tâche, de la plus haute à la plus basse priorité
+pub fn lane_keys(task_type: &TaskType) -> Vec<String> {
+    TaskPriority::ALL
+        .iter()
+        .map(|priority| lane_key(task_type, *priority))
+        .collect()
+}
+
+/// Liste de notification : un jeton par tâche enfilée, sert au dequeue bloquant
+pub fn notify_key(task_type: &TaskType) -> String {
+    format!("queue:{}:notify", task_type)
+}
+
+/// Sorted set des tâches différées d'un type : membre = task_id, score = run_at (ms epoch)
+pub fn scheduled_key(task_type: &TaskType) -> String {
+    format!("queue:{}:scheduled", task_type)
+}
+
+/// Hash de la dead-letter queue d'un type : champ = id de l'entrée, valeur = `DeadLetter` JSON
+pub fn dead_letter_key(task_type: &TaskType) -> String {
+    format!("queue:{}:dlq", task_type)
+}
+
+/// Hash des tâches en pause d'un type : champ = task_id, valeur = task_id
+/// (retenues hors des queues, ré-enfilées à la reprise)
+pub fn paused_key(task_type: &TaskType) -> String {
+    format!("queue:{}:paused", task_type)
+}
+
+/// Nom de la liste de traitement (in-flight) d'un worker
+pub fn processing_key(worker_id: &str) -> String {
+    format!("processing:{}", worker_id)
+}
+
+/// Ordre de consultation de N entrées pondérées pour le n-ième tirage.
+///
+/// L'entrée élue par le cycle pondéré passe en tête, les autres suivent dans
+/// leur ordre de déclaration. Renvoie des index dans `weights`.
+pub fn weighted_order(weights: &[u64], count: u64) -> Vec<usize> {
+    let cycle: u64 = weights.iter().sum();
+    if cycle == 0 {
+        return (0..weights.len()).collect();
+    }
+    let mut slot = count % cycle;
+
+    let mut leading = 0;
+    for (index, weight) in weights.iter().enumerate() {
+        if slot < *weight {
+            leading = index;
+            break;
+        }
+        slot -= weight;
+    }
+
+    let mut order = vec![leading];
+    order.extend((0..weights.len()).filter(|index| *index != leading));
+    order
+}
+
+/// Ordre de consultation des lanes pour le n-ième dequeue d'un worker.
+///
+/// La lane élue par le cycle pondéré passe en tête, les autres suivent par
+/// priorité décroissante.
+pub fn lane_order(dequeue_count: u64) -> Vec<TaskPriority> {
+    let weights: Vec<u64> = LANE_WEIGHTS.iter().map(|(_, weight)| *weight).collect();
+
+    weighted_order(&weights, dequeue_count)
+        .into_iter()
+        .map(|index| LANE_WEIGHTS[index].0)
+        .collect()
+}
+
+/// Parse une liste de types pondérés, par exemple `video:1,image:3`.
+///
+/// Le poids est optionnel (1 par défaut) et doit être strictement positif ;
+/// un type ne peut apparaître qu'une fois.
+pub fn parse_queue_weights(spec: &str) -> Result<Vec<(TaskType, u64)>, String> {
+    let mut queues: Vec<(TaskType, u64)> = Vec::new();
+
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (name, weight) = match entry.split_once(':') {
+            Some((name, weight)) => {
+                let weight = weight
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid weight in '{}'", entry))?;
+                (name.trim(), weight)
+            }
+            None => (entry, 1),
+        };
+
+        if weight == 0 {
+            return Err(format!("Weight must be at least 1 in '{}'", entry));
+        }
+
+        let task_type: TaskType = name.parse()?;
+        if queues.iter().any(|(existing, _)| *existing == task_type) {
+            return Err(format!("Task type '{}' listed more than once", name));
+        }
+
+        queues.push((task_type, weight));
+    }
+
+    if queues.is_empty() {
+        return Err("No task type given".to_string());
+    }
+
+    Ok(queues)
+}
+
+/// task_id désigné par une entrée de queue.
+///
+/// Les entrées écrites avant le passage aux ids contiennent la tâche
+/// sérialisée ; `None` si une telle entrée est illisible.
+pub fn entry_task_id(entry: &str) -> Option<String> {
+    if !entry.starts_with('{') {
+        return Some(entry.to_string()).filter(|id| !id.is_empty());
+    }
+
+    serde_json::from_str::<serde_json::Value>(entry)
+        .ok()?
+        .get("task_id")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Ajoute au pipeline l'enfilage d'une tâche dans sa lane et son jeton de notification
+pub fn push_task(pipe: &mut redis::Pipeline, task: &Task) {
+    pipe.lpush(lane_key(&task.task_type, task.priority), &task.id)
+        .ignore()
+        .lpush(notify_key(&task.task_type), 1)
+        .ignore();
+}
+
+/// Comme `push_task`, mais en tête de lane : la tâche sera la prochaine servie
+pub fn push_task_front(pipe: &mut redis::Pipeline, task: &Task) {
+    pipe.rpush(lane_key(&task.task_type, task.priority), &task.id)
+        .ignore()
+        .lpush(notify_key(&task.task_type), 1)
+        .ignore();
+}
+
+/// Ajoute au pipeline la mise en attente d'une tâche jusqu'à `at_ms` (ms epoch)
+pub fn schedule_task(pipe: &mut redis::Pipeline, task: &Task, at_ms: i64) {
+    pipe.zadd(scheduled_key(&task.task_type), &task.id, at_ms).ignore();
+}
+
+/// Déplace atomiquement la première tâche disponible vers la liste de
+/// traitement et pose sa lease dans la même opération : une tâche sortie de
+/// sa lane est toujours visible, sous lease, par la réconciliation.
+///
+/// KEYS[1] = liste de traitement, KEYS[2] = leases, KEYS[3] = registre des
+/// listes de traitement, puis des paires (lane, liste de notification de son
+/// type) dans l'ordre de consultation
+/// ARGV[1] = "1" pour consommer un jeton de notification (dequeue non bloquant),
+/// ARGV[2] = échéance de la lease (ms epoch)
+///
+/// Renvoie `{lane, entrée}` pour que l'appelant sache de quelle queue vient la
+/// tâche. Une entrée illisible n'a pas de lease : elle part en dead-letter.
+pub fn dequeue_script() -> Script {
+    Script::new(
+        r#"
+        for i = 4, #KEYS, 2 do
+            local entry = redis.call('LMOVE', KEYS[i], KEYS[1], 'RIGHT', 'LEFT')
+            if entry then
+                if ARGV[1] == '1' then
+                    redis.call('RPOP', KEYS[i + 1])
+                end
+                local task_id = entry
+                if string.sub(entry, 1, 1) == '{' then
+                    local ok, decoded = pcall(cjson.decode, entry)
+                    task_id = nil
+                    if ok and type(decoded) == 'table' and type(decoded['task_id']) == 'string' then
+                        task_id = decoded['task_id']
+                    end
+                end
+                if task_id and task_id ~= '' then
+                    redis.call('ZADD', KEYS[2], ARGV[2], task_id)
+                end
+                redis.call('SADD', KEYS[3], KEYS[1])
+                return {KEYS[i], entry}
+            end
+        end
+        return false
+        "#,
+    )
+}
+
+/// Remet une tâche dont la lease a expiré en tête de sa lane d'origine.
+///
+/// KEYS[1] = liste de traitement, KEYS[2] = leases, KEYS[3] = lane,
+/// KEYS[4] = liste de notification
+/// ARGV[1] = entrée exacte de la liste de traitement, ARGV[2] = task_id
+///
+/// Le `LREM` garantit qu'un seul reaper peut rendre la tâche, même si
+/// plusieurs workers scannent la même liste en parallèle.
+pub fn reap_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('LREM', KEYS[1], 1, ARGV[1]) > 0 then
+            redis.call('RPUSH', KEYS[3], ARGV[2])
+            redis.call('LPUSH', KEYS[4], 1)
+            redis.call('ZREM', KEYS[2], ARGV[2])
+            return 1
+        end
+        return 0
+        "#,
+    )
+}
+
+/// Promeut une tâche différée arrivée à échéance vers sa lane.
+///
+/// KEYS[1] = sorted set des tâches différées, KEYS[2] = lane,
+/// KEYS[3] = liste de notification
+/// ARGV[1] = membre différé, ARGV[2] = task_id à enfiler
+///
+/// Comme pour le reaper, le `ZREM` désigne un seul gagnant entre promoteurs.
+pub fn promote_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('ZREM', KEYS[1], ARGV[1]) > 0 then
+            redis.call('LPUSH', KEYS[2], ARGV[2])
+            redis.call('LPUSH', KEYS[3], 1)
+            return 1
+        end
+        return 0
+        "#,
+    )
+}
+
+/// Rejoue une entrée de la dead-letter queue.
+///
+/// KEYS[1] = hash de la DLQ, KEYS[2] = lane, KEYS[3] = liste de notification
+/// ARGV[1] = id de l'entrée, ARGV[2] = task_id à enfiler
+///
+/// Le `HDEL` garantit qu'une entrée n'est rejouée qu'une seule fois.
+pub fn replay_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('HDEL', KEYS[1], ARGV[1]) > 0 then
+            redis.call('LPUSH', KEYS[2], ARGV[2])
+            redis.call('LPUSH', KEYS[3], 1)
+            return 1
+        end
+        return 0
+        "#,
+    )
+}
+
+/// Met de côté une tâche en attente (lane ou sorted set des différées).
+///
+/// KEYS[1] = lane ou sorted set d'origine, KEYS[2] = hash des tâches en pause
+/// ARGV[1] = task_id, ARGV[2] = "list" ou "zset"
+///
+/// Renvoie 0 si la tâche n'y est plus (prise par un worker entre-temps).
+pub fn pause_script() -> Script {
+    Script::new(
+        r#"
+        local removed
+        if ARGV[2] == 'zset' then
+            removed = redis.call('ZREM', KEYS[1], ARGV[1])
+        else
+            removed = redis.call('LREM', KEYS[1], 1, ARGV[1])
+        end
+        if removed > 0 then
+            redis.call('HSET', KEYS[2], ARGV[1], ARGV[1])
+            return 1
+        end
+        return 0
+        "#,
+    )
+}
+
+/// Remet en queue une tâche mise de côté par une pause.
+///
+/// KEYS[1] = hash des tâches en pause, KEYS[2] = lane, KEYS[3] = liste de
+/// notification, KEYS[4] = sorted set des différées
+/// ARGV[1] = task_id, ARGV[2] = échéance (ms epoch) ou "" pour enfiler directement
+///
+/// Renvoie 0 si la tâche n'était pas retenue (elle était en cours de traitement).
+pub fn resume_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('HDEL', KEYS[1], ARGV[1]) == 0 then
+            return 0
+        end
+        if ARGV[2] ~= '' then
+            redis.call('ZADD', KEYS[4], ARGV[2], ARGV[1])
+        else
+            redis.call('LPUSH', KEYS[2], ARGV[1])
+            redis.call('LPUSH', KEYS[3], 1)
+        end
+        return 1
+        "#,
+    )
+}
+
+/// Retire une tâche en attente de l'endroit où elle se trouve (lane, différées
+/// ou pauses), ou prévient le worker qui la traite.
+///
+/// KEYS[1] = lane, KEYS[2] = liste de notification, KEYS[3] = sorted set des
+/// différées, KEYS[4] = hash des tâches en pause
+/// ARGV[1] = task_id, ARGV[2] = canal d'annulation de la tâche
+///
+/// Renvoie 1 si la tâche a été retirée : plus aucun worker ne peut la prendre
+/// et l'appelant la passe `cancelled`. Sinon (0), elle a déjà été sortie de la
+/// queue : le message d'annulation est publié dans le même script, et le worker
+/// qui la détient la trouvera `cancelling` dans MongoDB.
+pub fn cancel_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('LREM', KEYS[1], 1, ARGV[1]) > 0 then
+            redis.call('RPOP', KEYS[2])
+            return 1
+        end
+        if redis.call('ZREM', KEYS[3], ARGV[1]) > 0 then
+            return 1
+        end
+        if redis.call('HDEL', KEYS[4], ARGV[1]) > 0 then
+            return 1
+        end
+        redis.call('PUBLISH', ARGV[2], 'cancel')
+        return 0
+        "#,
+    )
+}
+
+/// Enfile une tâche seulement si elle n'est nulle part dans Redis : ni dans
+/// sa lane, ni dans les différées, ni en pause, ni sous lease chez un worker.
+///
+/// KEYS[1] = lane, KEYS[2] = liste de notification, KEYS[3] = sorted set des
+/// différées, KEYS[4] = hash des tâches en pause, KEYS[5] = leases
+/// ARGV[1] = task_id, ARGV[2] = échéance (ms epoch) ou "" pour enfiler directement
+///
+/// Sert au relais de l'outbox et à la réconciliation, qui peuvent repasser sur
+/// une tâche déjà enfilée. Renvoie 1 si la tâche a été enfilée.
+pub fn enqueue_if_absent_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('LPOS', KEYS[1], ARGV[1]) then
+            return 0
+        end
+        if redis.call('ZSCORE', KEYS[3], ARGV[1]) then
+            return 0
+        end
+        if redis.call('HEXISTS', KEYS[4], ARGV[1]) == 1 then
+            return 0
+        end
+        if redis.call('ZSCORE', KEYS[5], ARGV[1]) then
+            return 0
+        end
+        if ARGV[2] ~= '' then
+            redis.call('ZADD', KEYS[3], ARGV[2], ARGV[1])
+        else
+            redis.call('LPUSH', KEYS[1], ARGV[1])
+            redis.call('LPUSH', KEYS[2], 1)
+        end
+        return 1
+        "#,
+    )
+}
+
+/// Retire une liste de traitement du registre si elle est vide.
+///
+/// KEYS[1] = registre des listes, KEYS[2] = liste de traitement
+///
+/// Le test de longueur et le `SREM` sont faits atomiquement pour ne jamais
+/// oublier une liste qu'un worker vient de remplir.
+pub fn prune_processing_list_script() -> Script {
+    Script::new(
+        r#"
+        if redis.call('LLEN', KEYS[2]) == 0 then
+            return redis.call('SREM', KEYS[1], KEYS[2])
+        end
+        return 0
+        "#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_keys() {
+        assert_eq!(lane_key(&TaskType::VideoCompression, TaskPriority::Normal), "queue:video");
+        assert_eq!(lane_key(&TaskType::VideoCompression, TaskPriority::High), "queue:video:high");
+        assert_eq!(lane_key(&TaskType::ImageOptimization, TaskPriority::Low), "queue:image:low");
+        assert_eq!(notify_key(&TaskType::AudioProcessing), "queue:audio:notify");
+        assert_eq!(scheduled_key(&TaskType::AudioProcessing), "queue:audio:scheduled");
+        assert_eq!(dead_letter_key(&TaskType::ImageOptimization), "queue:image:dlq");
+        assert_eq!(paused_key(&TaskType::VideoCompression), "queue:video:paused");
+        assert_eq!(processing_key("worker-1"), "processing:worker-1");
+    }
+
+    #[test]
+    fn test_entry_task_id() {
+        assert_eq!(entry_task_id("a1b2").as_deref(), Some("a1b2"));
+        assert_eq!(entry_task_id(r#"{"task_id":"a1b2","status":"queued"}"#).as_deref(), Some("a1b2"));
+        assert_eq!(entry_task_id(r#"{"status":"queued"}"#), None);
+        assert_eq!(entry_task_id("{not json"), None);
+        assert_eq!(entry_task_id(""), None);
+    }
+
+    #[test]
+    fn test_lane_order_follows_weights() {
+        let mut leading = std::collections::HashMap::new();
+        for n in 0..10 {
+            let order = lane_order(n);
+            assert_eq!(order.len(), 3);
+            *leading.entry(order[0]).or_insert(0) += 1;
+        }
+
+        assert_eq!(leading[&TaskPriority::High], 6);
+        assert_eq!(leading[&TaskPriority::Normal], 3);
+        assert_eq!(leading[&TaskPriority::Low], 1);
+    }
+
+    #[test]
+    fn test_weighted_order_across_queues() {
+        let mut leading = [0; 2];
+        for n in 0..8 {
+            leading[weighted_order(&[1, 3], n)[0]] += 1;
+        }
+
+        assert_eq!(leading, [2, 6]);
+        assert_eq!(weighted_order(&[1, 3], 0), vec![0, 1]);
+        assert_eq!(weighted_order(&[1, 3], 1), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_parse_queue_weights() {
+        assert_eq!(
+            parse_queue_weights("video:1,image:3").unwrap(),
+            vec![(TaskType::VideoCompression, 1), (TaskType::ImageOptimization, 3)]
+        );
+        assert_eq!(
+            parse_queue_weights("audio").unwrap(),
+            vec![(TaskType::AudioProcessing, 1)]
+        );
+
+        assert!(parse_queue_weights("").is_err());
+        assert!(parse_queue_weights("video:0").is_err());
+        assert!(parse_queue_weights("video:x").is_err());
+        assert!(parse_queue_weights("pdf:2").is_err());
+        assert!(parse_queue_weights("video,video:2").is_err());
+    }
+
+    #[test]
+    fn test_lane_order_keeps_priority_after_leader() {
+        assert_eq!(
+            lane_order(0),
+            vec![TaskPriority::High, TaskPriority::Normal, TaskPriority::Low]
+        );
+        assert_eq!(
+            lane_order(9),
+            vec![TaskPriority::Low, TaskPriority::High, TaskPriority::Normal]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Récupération des tâches orphelines : restées `processing` dans MongoDB
+//! alors que plus aucun worker ne les traite (crash, panne de Redis...).
+
+use crate::dependencies::{self, DependencyError};
+use crate::models::{DeadLetter, InvalidTransition, Task, TaskStatus, WorkerInfo};
+use crate::queue;
+use crate::retry::RetryPolicy;
+use crate::utils::mongo_utils::{status_filter, task_update};
+use chrono::{DateTime, Utc};
+use mongodb::bson::{self, doc};
+use mongodb::Database;
+use redis::aio::ConnectionLike;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Erreurs de la récupération
+#[derive(Debug, thiserror::Error)]
+pub enum RecoveryError {
+    #[error("MongoDB error: {0}")]
+    Mongo(#[from] mongodb::error::Error),
+
+    #[error("BSON serialization error: {0}")]
+    Bson(#[from] bson::ser::Error),
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Transition(#[from] InvalidTransition),
+
+    #[error(transparent)]
+    Dependency(#[from] DependencyError),
+}
+
+/// Décision prise pour une tâche `processing` examinée
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryDecision {
+    /// Encore traitée : lease valide ou worker vivant qui la déclare
+    Alive(String),
+    /// Remise en attente de retry, la tentative perdue est comptée
+    Retried(DateTime<Utc>),
+    /// Retries épuisés : en échec et en dead-letter queue
+    Failed,
+}
+
+/// Bilan d'une passe de récupération
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryReport {
+    pub examined: usize,
+    pub retried: usize,
+    pub failed: usize,
+}
+
+/// Raison pour laquelle une tâche est encore traitée, ou `None` si elle est orpheline
+fn liveness(
+    task: &Task,
+    lease_deadline: Option<i64>,
+    workers: &HashMap<String, WorkerInfo>,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    if lease_deadline.is_some_and(|deadline| deadline >= now.timestamp_millis()) {
+        return Some("lease still valid".to_string());
+    }
+
+    // Un worker redémarré sous le même id ne déclare plus ses anciennes tâches
+    let worker = task.worker_id.as_ref().and_then(|worker_id| workers.get(worker_id))?;
+    if worker.is_alive_at(now) && worker.current_task_ids.contains(&task.id) {
+        return Some(format!("worker {} still alive", worker.worker_id));
+    }
+
+    None
+}
+
+/// Examine les tâches `processing` inchangées depuis `stale_after` et sans
+/// lease valide ni worker vivant : chacune est remise en retry, ou passe en
+/// échec si ses retries sont épuisés. Chaque décision est journalisée.
+///
+/// Les écritures ne s'appliquent que si la tâche est encore `processing` :
+/// plusieurs récupérations concurrentes sont sans danger.
+pub async fn recover_orphaned_tasks<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    policy: &RetryPolicy,
+    stale_after: Duration,
+) -> Result<RecoveryReport, RecoveryError> {
+    let collection = db.collection::<Task>("tasks");
+    let now = Utc::now();
+    let cutoff = now - chrono::Duration::from_std(stale_after).unwrap_or(chrono::Duration::zero());
+
+    let entries: HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(queue::WORKERS_KEY)
+        .query_async(conn)
+        .await?;
+    let workers: HashMap<String, WorkerInfo> = entries
+        .into_iter()
+        .filter_map(|(worker_id, json)| serde_json::from_str(&json).ok().map(|info| (worker_id, info)))
+        .collect();
+
+    let filter = doc! {
+        "status": TaskStatus::Processing.to_string(),
+        "updated_at": { "$lte": bson::to_bson(&cutoff)? },
+    };
+    let mut stale = Vec::new();
+    let mut cursor = collection.find(filter, None).await?;
+    while cursor.advance().await? {
+        stale.push(cursor.deserialize_current()?);
+    }
+
+    let mut report = RecoveryReport::default();
+    // Les dates sont comparées sous forme de texte : on revérifie l'ancienneté
+    for task in stale.into_iter().filter(|task: &Task| task.updated_at <= cutoff) {
+        report.examined += 1;
+
+        let lease_deadline: Option<f64> = redis::cmd("ZSCORE")
+            .arg(queue::LEASES_KEY)
+            .arg(&task.id)
+            .query_async(conn)
+            .await?;
+
+        let decision = match liveness(&task, lease_deadline.map(|deadline| deadline as i64), &workers, now) {
+            Some(reason) => RecoveryDecision::Alive(reason),
+            None => match recover_task(db, conn, policy, task.clone()).await? {
+                Some(decision) => decision,
+                // Terminée ou reprise entre-temps
+                None => continue,
+            },
+        };
+
+        match decision {
+            RecoveryDecision::Alive(ref reason) => {
+                tracing::info!(task_id = %task.id, reason = %reason, "Processing task still alive, left untouched");
+            }
+            RecoveryDecision::Retried(retry_at) => {
+                tracing::warn!(
+                    task_id = %task.id,
+                    worker_id = ?task.worker_id,
+                    retry_at = %retry_at,
+                    "Orphaned processing task scheduled for retry"
+                );
+                report.retried += 1;
+            }
+            RecoveryDecision::Failed => {
+                tracing::error!(
+                    task_id = %task.id,
+                    worker_id = ?task.worker_id,
+                    "Orphaned processing task failed permanently, moved to dead-letter queue"
+                );
+                report.failed += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Compte la tentative perdue et remet la tâche en retry ou en échec ;
+/// renvoie `None` si la tâche n'était plus `processing`
+async fn recover_task<C: ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    policy: &RetryPolicy,
+    mut task: Task,
+) -> Result<Option<RecoveryDecision>, RecoveryError> {
+    let collection = db.collection::<Task>("tasks");
+    let worker_id = task.worker_id.clone();
+
+    task.increment_retry();
+    task.record_error(format!(
+        "Worker {} lost while processing",
+        worker_id.as_deref().unwrap_or("unknown")
+    ));
+
+    let decision = if task.should_retry() {
+        let delay = chrono::Duration::from_std(policy.next_delay(task.retry_count))
+            .unwrap_or(chrono::Duration::zero());
+        let retry_at = Utc::now() + delay;
+        task.next_retry_at = Some(retry_at);
+        task.update_status(TaskStatus::Retrying)?;
+        RecoveryDecision::Retried(retry_at)
+    } else {
+        task.update_status(TaskStatus::Failed)?;
+        RecoveryDecision::Failed
+    };
+
+    let result = collection
+        .update_one(status_filter(&task.id, &[TaskStatus::Processing]), task_update(&task)?, None)
+        .await?;
+    if result.matched_count == 0 {
+        return Ok(None);
+    }
+
+    // Retirer les restes de la tentative perdue, y compris une remise en
+    // queue par le reaper, pour que la tâche ne soit pas prise deux fois
+    let mut pipe = redis::pipe();
+    pipe.atomic()
+        .zrem(queue::LEASES_KEY, &task.id)
+        .ignore()
+        .lrem(queue::lane_key(&task.task_type, task.priority), 0, &task.id)
+        .ignore();
+    if let Some(ref worker_id) = worker_id {
+        pipe.lrem(queue::processing_key(worker_id), 1, &task.id).ignore();
+    }
+    match decision {
+        RecoveryDecision::Retried(retry_at) => {
+            queue::schedule_task(&mut pipe, &task, retry_at.timestamp_millis());
+        }
+        _ => {
+            let entry = DeadLetter::from_failed_task(&task, task.id.clone());
+            pipe.hset(queue::dead_letter_key(&task.task_type), &entry.id, serde_json::to_string(&entry)?)
+                .ignore();
+        }
+    }
+    let _: () = pipe.query_async(conn).await?;
+
+    if decision == RecoveryDecision::Failed {
+        dependencies::settle_dependents(db, conn, &task.id).await?;
+    }
+
+    Ok(Some(decision))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MediaFile, MediaType, TaskType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_liveness() {
+        let media = MediaFile::new(
+            "test-123".to_string(),
+            MediaType::Image,
+            PathBuf::from("/path/to/image.png"),
+            2048,
+            "image.png".to_string(),
+            "image/png".to_string(),
+        );
+        let mut task = Task::new(TaskType::ImageOptimization, media);
+        task.assign_worker("worker-1");
+        let now = Utc::now();
+
+        let mut worker = WorkerInfo::new(
+            "worker-1".to_string(),
+            vec![TaskType::ImageOptimization],
+            "host-a".to_string(),
+            "0.1.0".to_string(),
+            5,
+            1,
+        );
+        let mut workers = HashMap::new();
+        workers.insert(worker.worker_id.clone(), worker.clone());
+
+        // Lease valide
+        assert!(liveness(&task, Some(now.timestamp_millis() + 1000), &workers, now).is_some());
+
+        // Lease expirée, worker vivant mais redémarré : il ne déclare plus la tâche
+        assert!(liveness(&task, Some(now.timestamp_millis() - 1000), &workers, now).is_none());
+
+        worker.current_task_ids.push(task.id.clone());
+        workers.insert(worker.worker_id.clone(), worker.clone());
+        assert!(liveness(&task, None, &workers, now).is_some());
+
+        // Worker mort
+        worker.last_heartbeat = now - chrono::Duration::minutes(5);
+        workers.insert(worker.worker_id.clone(), worker);
+        assert!(liveness(&task, None, &workers, now).is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// Politique de backoff exponentiel pour les retries de tâches
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Délai avant le premier retry
+    pub base: Duration,
+    /// Multiplicateur appliqué à chaque tentative supplémentaire
+    pub factor: f64,
+    /// Délai maximal entre deux tentatives
+    pub cap: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(2),
+            factor: 2.0,
+            cap: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Délai sans jitter avant la tentative `attempt` (1 = premier retry)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1) as i32;
+        let secs = self.base.as_secs_f64() * self.factor.powi(exponent);
+
+        if !secs.is_finite() || secs >= self.cap.as_secs_f64() {
+            self.cap
+        } else {
+            Duration::from_secs_f64(secs)
+        }
+    }
+
+    /// Délai avec jitter : entre la moitié et la totalité du backoff.
+    ///
+    /// `jitter` est un tirage dans `[0, 1]`, exposé pour les tests.
+    pub fn delay_with_jitter(&self, attempt: u32, jitter: f64) -> Duration {
+        let backoff = self.backoff(attempt);
+        backoff.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+    }
+
+    /// Délai avant la tentative `attempt`, jitter aléatoire inclus
+    pub fn next_delay(&self, attempt: u32) -> Duration {
+        let jitter = rand::thread_rng().gen::<f64>();
+        self.delay_with_jitter(attempt, jitter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_exponentially() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy {
+            base: Duration::from_secs(10),
+            factor: 3.0,
+            cap: Duration::from_secs(60),
+        };
+
+        assert_eq!(policy.backoff(3), Duration::from_secs(60));
+        assert_eq!(policy.backoff(200), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.delay_with_jitter(2, 0.0), Duration::from_secs(2));
+        assert_eq!(policy.delay_with_jitter(2, 1.0), Duration::from_secs(4));
+
+        for _ in 0..100 {
+            let delay = policy.next_delay(3);
+            assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(8));
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub mod redis_utils;
 pub mod mongo_utils;
+pub mod shutdown;
 
 pub use redis_utils::RedisClient;
 pub use mongo_utils::MongoClient;
+pub use shutdown::shutdown_signal;
@@ -1,5 +1,8 @@
-use mongodb::{Client, Database, bson::doc};
-use crate::models::Task;
+use mongodb::error::{ErrorKind, WriteError, WriteFailure};
+use mongodb::options::IndexOptions;
+use mongodb::{Client, Database, IndexModel, bson::{self, doc, Document}};
+use crate::models::task::MAX_STATUS_HISTORY;
+use crate::models::{Job, Task, TaskStatus};
 
 pub struct MongoClient {
     client: Client,
@@ -55,6 +58,130 @@ impl MongoClient {
     }
 }
 
+/// Crée les index des collections `tasks` et `jobs` (idempotent)
+pub async fn ensure_indexes(db: &Database) -> Result<(), mongodb::error::Error> {
+    let collection = db.collection::<Task>("tasks");
+
+    // Unique seulement parmi les tâches créées avec une clé
+    let idempotency = IndexModel::builder()
+        .keys(doc! { "idempotency_key": 1 })
+        .options(
+            IndexOptions::builder()
+                .name("idempotency_key_unique".to_string())
+                .unique(true)
+                .partial_filter_expression(doc! { "idempotency_key": { "$exists": true } })
+                .build(),
+        )
+        .build();
+
+    // Tâches d'un job
+    let job = IndexModel::builder()
+        .keys(doc! { "job_id": 1 })
+        .options(IndexOptions::builder().name("job_id".to_string()).build())
+        .build();
+
+    // Dépendants d'une tâche
+    let dependents = IndexModel::builder()
+        .keys(doc! { "depends_on": 1 })
+        .options(IndexOptions::builder().name("depends_on".to_string()).build())
+        .build();
+
+    // Tâches à échéance, parcourues par le sweeper d'expiration
+    let expiry = IndexModel::builder()
+        .keys(doc! { "expires_at": 1 })
+        .options(
+            IndexOptions::builder()
+                .name("expires_at".to_string())
+                .partial_filter_expression(doc! { "expires_at": { "$exists": true } })
+                .build(),
+        )
+        .build();
+
+    // Tâches dont l'enqueue n'est pas confirmé, parcourues par le relais de l'outbox
+    let outbox = IndexModel::builder()
+        .keys(doc! { "outbox.next_attempt_at": 1 })
+        .options(
+            IndexOptions::builder()
+                .name("outbox_next_attempt_at".to_string())
+                .partial_filter_expression(doc! { "outbox": { "$exists": true } })
+                .build(),
+        )
+        .build();
+
+    collection.create_indexes([idempotency, job, dependents, expiry, outbox], None).await?;
+
+    let job_id = IndexModel::builder()
+        .keys(doc! { "job_id": 1 })
+        .options(IndexOptions::builder().name("job_id_unique".to_string()).unique(true).build())
+        .build();
+
+    db.collection::<Job>("jobs").create_index(job_id, None).await?;
+    Ok(())
+}
+
+/// Vrai si l'écriture a été refusée par un index unique
+pub fn is_duplicate_key_error(err: &mongodb::error::Error) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(WriteError { code: 11000, .. }))
+    )
+}
+
+/// Filtre d'une écriture de statut : appliquée seulement si la tâche est
+/// encore dans l'un des statuts `from`
+pub fn status_filter(task_id: &str, from: &[TaskStatus]) -> Document {
+    let from: Vec<String> = from.iter().map(|status| status.to_string()).collect();
+    doc! { "task_id": task_id, "status": { "$in": from } }
+}
+
+/// Filtre d'une écriture qui fait passer la tâche à `to` : la machine à états
+/// est vérifiée contre le statut stocké, au moment de l'écriture
+pub fn transition_filter(task_id: &str, to: &TaskStatus) -> Document {
+    status_filter(task_id, &to.predecessors())
+}
+
+/// `$set` des champs modifiés par `Task::update_status`
+pub fn status_update(task: &Task) -> Result<Document, bson::ser::Error> {
+    let mut update = doc! {
+        "$set": {
+            "status": task.status.to_string(),
+            "updated_at": bson::to_bson(&task.updated_at)?,
+            "started_at": bson::to_bson(&task.started_at)?,
+            "completed_at": bson::to_bson(&task.completed_at)?,
+            "next_retry_at": bson::to_bson(&task.next_retry_at)?,
+            "worker_id": bson::to_bson(&task.worker_id)?,
+        }
+    };
+    push_history(&mut update, task)?;
+    Ok(update)
+}
+
+/// Mise à jour complète d'une tâche. L'historique stocké n'est pas écrasé :
+/// seules les transitions pas encore écrites y sont ajoutées. Le marqueur
+/// d'outbox n'est écrit que par l'outbox.
+pub fn task_update(task: &Task) -> Result<Document, bson::ser::Error> {
+    let mut fields = bson::to_document(task)?;
+    fields.remove("history");
+    fields.remove("outbox");
+
+    let mut update = doc! { "$set": fields };
+    push_history(&mut update, task)?;
+    Ok(update)
+}
+
+fn push_history(update: &mut Document, task: &Task) -> Result<(), bson::ser::Error> {
+    let transitions = task.unsaved_transitions();
+    if !transitions.is_empty() {
+        update.insert("$push", doc! {
+            "history": {
+                "$each": bson::to_bson(transitions)?,
+                "$slice": -(MAX_STATUS_HISTORY as i64),
+            }
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,6 +191,47 @@ mod tests {
     // Note: Ces tests nécessitent une instance MongoDB en cours d'exécution
     // Vous pouvez les ignorer avec: cargo test -- --skip mongo
 
+    #[test]
+    fn test_transition_filter_uses_predecessors() {
+        let filter = transition_filter("task-1", &TaskStatus::Retrying);
+        let allowed = filter.get_document("status").unwrap().get_array("$in").unwrap();
+
+        assert_eq!(filter.get_str("task_id").unwrap(), "task-1");
+        assert!(allowed.contains(&bson::Bson::String("processing".to_string())));
+        assert!(!allowed.contains(&bson::Bson::String("cancelling".to_string())));
+    }
+
+    #[test]
+    fn test_task_update_only_pushes_unsaved_transitions() {
+        let media = MediaFile::new(
+            "test-123".to_string(),
+            MediaType::Image,
+            PathBuf::from("/path/to/image.jpg"),
+            2048,
+            "image.jpg".to_string(),
+            "image/jpeg".to_string(),
+        );
+
+        let mut task = Task::new(TaskType::ImageOptimization, media);
+        task.update_status(TaskStatus::Queued).unwrap();
+        task.mark_transitions_saved();
+        task.update_status(TaskStatus::Processing).unwrap();
+        task.outbox = Some(crate::outbox::OutboxEntry::new());
+
+        let update = task_update(&task).unwrap();
+        assert!(!update.get_document("$set").unwrap().contains_key("history"));
+        assert!(!update.get_document("$set").unwrap().contains_key("outbox"));
+
+        let pushed = update
+            .get_document("$push").unwrap()
+            .get_document("history").unwrap()
+            .get_array("$each").unwrap();
+        assert_eq!(pushed.len(), 1);
+
+        task.mark_transitions_saved();
+        assert!(!status_update(&task).unwrap().contains_key("$push"));
+    }
+
     #[tokio::test]
     #[ignore] // Ignorer par défaut
     async fn test_mongo_connection() {
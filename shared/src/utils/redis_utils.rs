@@ -1,6 +1,6 @@
 use redis::{Client, RedisError, AsyncCommands};
-use serde::Serialize;
 use crate::models::Task;
+use crate::queue;
 
 pub struct RedisClient {
     client: Client,
@@ -11,7 +11,7 @@ impl RedisClient {
         let client = Client::open(redis_url)?;
         
         // Test connection
-        let mut conn = client.get_async_connection().await?;
+        let mut conn = client.get_multiplexed_async_connection().await?;
         let _: String = redis::cmd("PING").query_async(&mut conn).await?;
         
         tracing::info!("Successfully connected to Redis");
@@ -20,16 +20,12 @@ impl RedisClient {
     }
 
     pub async fn enqueue_task(&self, task: &Task) -> Result<(), RedisError> {
-        let mut conn = self.client.get_async_connection().await?;
-        let queue_name = format!("queue:{}", task.task_type);
-        let serialized = serde_json::to_string(task)
-            .map_err(|e| RedisError::from((
-                redis::ErrorKind::TypeError,
-                "Serialization error",
-                e.to_string()
-            )))?;
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let queue_name = queue::lane_key(&task.task_type, task.priority);
         
-        conn.lpush(&queue_name, serialized).await?;
+        let mut pipe = redis::pipe();
+        queue::push_task(pipe.atomic(), task);
+        let _: () = pipe.query_async(&mut conn).await?;
         
         tracing::debug!(
             task_id = %task.id,
@@ -42,7 +38,7 @@ impl RedisClient {
     }
 
     pub async fn get_queue_length(&self, queue_name: &str) -> Result<usize, RedisError> {
-        let mut conn = self.client.get_async_connection().await?;
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
         let len: usize = conn.llen(queue_name).await?;
         Ok(len)
     }
@@ -57,7 +53,6 @@ mod tests {
     use super::*;
     use crate::models::{MediaFile, MediaType, TaskType};
     use std::path::PathBuf;
-    use std::collections::HashMap;
 
     // Note: Ces tests nécessitent une instance Redis en cours d'exécution
     // Vous pouvez les ignorer avec: cargo test -- --skip redis
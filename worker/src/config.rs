@@ -0,0 +1,158 @@
+use shared::retry::RetryPolicy;
+use std::time::Duration;
+
+/// Lease minimale : elle est renouvelée au tiers de sa durée, une lease plus
+/// courte ferait boucler le renouvellement et expirer les tâches en cours
+const MIN_VISIBILITY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Paramètres d'exécution du moteur de worker
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Durée d'une lease avant que la tâche soit considérée comme abandonnée
+    pub visibility_timeout: Duration,
+    /// Intervalle entre deux passes du reaper
+    pub reaper_interval: Duration,
+    /// Durée maximale d'attente du dequeue bloquant avant de reboucler
+    pub block_timeout: Duration,
+    /// Intervalle entre deux passes du promoteur de tâches différées
+    pub promoter_interval: Duration,
+    /// Intervalle entre deux passes du sweeper des tâches expirées
+    pub expiry_sweep_interval: Duration,
+    /// Intervalle entre deux passes du relais de l'outbox
+    pub outbox_relay_interval: Duration,
+    /// Âge à partir duquel le relais reprend une entrée d'outbox à son compte
+    pub outbox_grace: Duration,
+    /// Intervalle entre deux passes de la réconciliation MongoDB / Redis
+    pub reconcile_interval: Duration,
+    /// Ancienneté minimale d'une tâche en attente avant d'être réconciliée
+    pub reconcile_min_age: Duration,
+    /// Ancienneté à partir de laquelle une tâche `processing` sans lease ni
+    /// worker vivant est récupérée au démarrage
+    pub recovery_stale_after: Duration,
+    /// Nombre de tâches traitées en parallèle par le process
+    pub concurrency: usize,
+    /// Délai laissé aux tâches en cours pour finir lors d'un arrêt
+    pub shutdown_grace: Duration,
+    /// Délai laissé au processor pour s'arrêter après un dépassement de timeout
+    pub timeout_grace: Duration,
+    /// Intervalle minimal entre deux publications de progression d'une tâche
+    pub progress_interval: Duration,
+    /// Avancée minimale de la progression entre deux publications
+    pub progress_min_step: f32,
+    /// Pas des checkpoints de progression écrits dans MongoDB
+    pub progress_checkpoint_step: f32,
+    /// Période des heartbeats envoyés au registre des workers
+    pub heartbeat_interval: Duration,
+    /// Backoff appliqué entre deux tentatives d'une tâche en échec
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            visibility_timeout: Duration::from_secs(30),
+            reaper_interval: Duration::from_secs(10),
+            block_timeout: Duration::from_secs(5),
+            promoter_interval: Duration::from_secs(1),
+            expiry_sweep_interval: Duration::from_secs(30),
+            outbox_relay_interval: Duration::from_secs(5),
+            outbox_grace: Duration::from_secs(30),
+            reconcile_interval: Duration::from_secs(300),
+            reconcile_min_age: Duration::from_secs(300),
+            recovery_stale_after: Duration::from_secs(300),
+            concurrency: 1,
+            shutdown_grace: Duration::from_secs(30),
+            timeout_grace: Duration::from_secs(5),
+            progress_interval: Duration::from_millis(500),
+            progress_min_step: 0.01,
+            progress_checkpoint_step: 0.25,
+            heartbeat_interval: Duration::from_secs(5),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Construit la configuration depuis les variables d'environnement
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        
+        Self {
+            visibility_timeout: env_secs("VISIBILITY_TIMEOUT_SECS")
+                .filter(|timeout| *timeout >= MIN_VISIBILITY_TIMEOUT)
+                .unwrap_or(default.visibility_timeout),
+            reaper_interval: env_secs("REAPER_INTERVAL_SECS")
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(default.reaper_interval),
+            block_timeout: env_secs("DEQUEUE_BLOCK_TIMEOUT_SECS")
+                .filter(|timeout| !timeout.is_zero())
+                .unwrap_or(default.block_timeout),
+            promoter_interval: env_secs("PROMOTER_INTERVAL_SECS")
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(default.promoter_interval),
+            expiry_sweep_interval: env_secs("EXPIRY_SWEEP_INTERVAL_SECS")
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(default.expiry_sweep_interval),
+            outbox_relay_interval: env_secs("OUTBOX_RELAY_INTERVAL_SECS")
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(default.outbox_relay_interval),
+            outbox_grace: env_secs("OUTBOX_GRACE_SECS")
+                .unwrap_or(default.outbox_grace),
+            reconcile_interval: env_secs("RECONCILE_INTERVAL_SECS")
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(default.reconcile_interval),
+            reconcile_min_age: env_secs("RECONCILE_MIN_AGE_SECS")
+                .unwrap_or(default.reconcile_min_age),
+            recovery_stale_after: env_secs("RECOVERY_STALE_AFTER_SECS")
+                .unwrap_or(default.recovery_stale_after),
+            concurrency: std::env::var("WORKER_CONCURRENCY")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|concurrency| *concurrency >= 1)
+                .unwrap_or(default.concurrency),
+            shutdown_grace: env_secs("SHUTDOWN_GRACE_PERIOD_SECS")
+                .unwrap_or(default.shutdown_grace),
+            timeout_grace: env_secs("TASK_TIMEOUT_GRACE_SECS")
+                .unwrap_or(default.timeout_grace),
+            progress_interval: std::env::var("PROGRESS_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(default.progress_interval),
+            progress_min_step: env_fraction("PROGRESS_MIN_STEP")
+                .unwrap_or(default.progress_min_step),
+            progress_checkpoint_step: env_fraction("PROGRESS_CHECKPOINT_STEP")
+                .filter(|step| *step > 0.0)
+                .unwrap_or(default.progress_checkpoint_step),
+            heartbeat_interval: env_secs("HEARTBEAT_INTERVAL_SECS")
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(default.heartbeat_interval),
+            retry_policy: RetryPolicy {
+                base: env_secs("RETRY_BACKOFF_BASE_SECS")
+                    .unwrap_or(default.retry_policy.base),
+                factor: std::env::var("RETRY_BACKOFF_FACTOR")
+                    .ok()
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|factor| *factor >= 1.0)
+                    .unwrap_or(default.retry_policy.factor),
+                cap: env_secs("RETRY_BACKOFF_CAP_SECS")
+                    .unwrap_or(default.retry_policy.cap),
+            },
+        }
+    }
+}
+
+fn env_secs(key: &str) -> Option<Duration> {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// Fraction de progression, entre 0 et 1
+fn env_fraction(key: &str) -> Option<f32> {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|fraction| (0.0..=1.0).contains(fraction))
+}
@@ -1,76 +1,156 @@
-use crate::processors::{TaskProcessor, ProgressCallback, CancelFlag};
+use crate::config::EngineConfig;
+use crate::processors::{TaskProcessor, ProgressCallback, PauseFlag};
 use anyhow::{Context, Result};
 use mongodb::Database;
+use redis::aio::ConnectionManager;
 use redis::Client as RedisClient;
-use shared::{Task, TaskStatus, TaskType, PubSubClient};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use tokio::sync::mpsc;
+use shared::progress::ProgressThrottle;
+use shared::{dependencies, outbox, recovery};
+use shared::pubsub::CONTROL_CHANNEL_PATTERN;
+use shared::utils::mongo_utils::{status_filter, status_update, task_update, transition_filter};
+use shared::models::INTERRUPTED_BY_SHUTDOWN;
+use shared::{queue, DeadLetter, Task, TaskCommand, TaskEvent, TaskStatus, TaskType, PubSubClient, WorkerInfo};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tokio::sync::{oneshot, watch, Semaphore};
 use tokio::time::{sleep, Duration};
+use futures_util::future::FutureExt;
 use futures_util::stream::StreamExt;
 
+/// Tâches en cours dans ce process, indexées par task_id. Une même tâche
+/// peut être livrée deux fois (lease perdue puis reprise) : chaque livraison
+/// a sa propre entrée.
+type InFlightTasks = Arc<Mutex<HashMap<String, Vec<InFlightControl>>>>;
+
+/// Source des jetons qui distinguent les livraisons d'une même tâche
+static NEXT_IN_FLIGHT_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+/// Pas de la prolongation du timeout d'une tâche en pause
+const PAUSE_TICK: Duration = Duration::from_secs(1);
+
+/// Leviers de contrôle d'une tâche en cours
+struct InFlightControl {
+    /// Jeton de la livraison, propre à chaque dequeue
+    token: u64,
+    /// Déclencheur d'arrêt, consommé par la première annulation ou interruption
+    stop: oneshot::Sender<StopReason>,
+    pause_flag: PauseFlag,
+}
+
+/// Raison pour laquelle une tâche en cours est interrompue
+enum StopReason {
+    /// Annulation demandée via l'API
+    Cancelled,
+    /// Arrêt du worker après expiration du délai de grâce
+    Shutdown,
+}
+
+/// Queue servie par le worker, avec son poids et son processor
+pub struct QueueBinding {
+    pub task_type: TaskType,
+    /// Part relative des dequeues attribuée à cette queue
+    pub weight: u64,
+    pub processor: Arc<dyn TaskProcessor>,
+}
+
+/// Issue du traitement d'une tâche dans `process_task`
+enum ProcessOutcome {
+    Finished(Result<()>),
+    Cancelled,
+    /// Interrompue par l'arrêt du worker, à remettre en queue telle quelle
+    Interrupted,
+}
+
 pub struct WorkerEngine {
-    redis_client: RedisClient,
+    /// Connexion longue durée pour les commandes courantes
+    conn: ConnectionManager,
+    /// Connexion dédiée au dequeue bloquant (BLMOVE bloque toute la connexion)
+    blocking_conn: ConnectionManager,
     mongo_db: Database,
-    processor: Arc<dyn TaskProcessor>,
-    queue_name: String,
+    /// Queues servies, dans l'ordre de déclaration
+    queues: Vec<QueueBinding>,
+    processing_key: String,
     worker_id: String,
+    config: EngineConfig,
+    /// Publication des événements de cycle de vie, souscription aux commandes
     pubsub_client: PubSubClient,
-    cancel_tx: mpsc::Sender<String>,
-    cancel_rx: mpsc::Receiver<String>,
+    /// Routage des annulations reçues par le listener vers la tâche concernée
+    in_flight: InFlightTasks,
+    /// Nombre de dequeues effectués, pilote la rotation pondérée des lanes
+    dequeue_count: AtomicU64,
+    dequeue_script: redis::Script,
+    /// Fiche du worker publiée dans le registre à chaque heartbeat
+    info: Arc<Mutex<WorkerInfo>>,
 }
 
 impl WorkerEngine {
-    pub fn new(
+    pub async fn new(
         redis_client: RedisClient,
         mongo_db: Database,
-        processor: Arc<dyn TaskProcessor>,
-        task_type: TaskType,
+        queues: Vec<QueueBinding>,
         worker_id: String,
-    ) -> Self {
-        let queue_name = format!("queue:{}", task_type);
-        let pubsub_client = PubSubClient::new(redis_client.clone());
-        let (cancel_tx, cancel_rx) = mpsc::channel(100);
+        config: EngineConfig,
+    ) -> Result<Self> {
+        let conn = redis_client
+            .get_connection_manager()
+            .await
+            .context("Failed to open Redis connection")?;
+        let blocking_conn = redis_client
+            .get_connection_manager()
+            .await
+            .context("Failed to open blocking Redis connection")?;
+        let processing_key = queue::processing_key(&worker_id);
+        let pubsub_client = PubSubClient::new(redis_client);
+        let info = WorkerInfo::new(
+            worker_id.clone(),
+            queues.iter().map(|binding| binding.task_type.clone()).collect(),
+            hostname(),
+            env!("CARGO_PKG_VERSION").to_string(),
+            config.heartbeat_interval.as_secs(),
+            config.concurrency,
+        );
         
-        Self {
-            redis_client,
+        Ok(Self {
+            conn,
+            blocking_conn,
             mongo_db,
-            processor,
-            queue_name,
+            queues,
+            processing_key,
             worker_id,
+            config,
             pubsub_client,
-            cancel_tx,
-            cancel_rx,
-        }
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            dequeue_count: AtomicU64::new(0),
+            dequeue_script: queue::dequeue_script(),
+            info: Arc::new(Mutex::new(info)),
+        })
     }
     
-    /// Spawne un listener global qui écoute tous les messages de cancellation
+    /// Spawne un listener global qui écoute les annulations et les commandes de tâches
     async fn spawn_cancel_listener(&self) {
-        let redis_client = self.redis_client.clone();
-        let cancel_tx = self.cancel_tx.clone();
+        let pubsub_client = self.pubsub_client.clone();
+        let in_flight = self.in_flight.clone();
         let worker_id = self.worker_id.clone();
         
         tokio::spawn(async move {
             tracing::info!(worker_id = %worker_id, "Starting global cancel listener");
             
-            // Créer une connexion pub/sub dédiée
-            let conn = match redis_client.get_async_connection().await {
-                Ok(conn) => conn,
+            // Connexion pub/sub dédiée, abonnée aux patterns
+            let patterns = vec!["task:cancel:*".to_string(), CONTROL_CHANNEL_PATTERN.to_string()];
+            let mut pubsub = match pubsub_client.psubscribe(patterns).await {
+                Ok(pubsub) => pubsub,
                 Err(e) => {
-                    tracing::error!("Failed to get Redis connection for pubsub: {}", e);
+                    tracing::error!("Failed to subscribe to task commands: {:#}", e);
                     return;
                 }
             };
             
-            let mut pubsub = conn.into_pubsub();
-            
-            // Subscribe au pattern
-            if let Err(e) = pubsub.psubscribe("task:cancel:*").await {
-                tracing::error!("Failed to psubscribe: {}", e);
-                return;
-            }
-            
-            tracing::info!(worker_id = %worker_id, "Successfully subscribed to task:cancel:*");
+            tracing::info!(
+                worker_id = %worker_id,
+                "Successfully subscribed to task:cancel:* and {}",
+                CONTROL_CHANNEL_PATTERN
+            );
             
             // Écouter les messages
             let mut pubsub_stream = pubsub.on_message();
@@ -79,16 +159,17 @@ impl WorkerEngine {
                 match pubsub_stream.next().await {
                     Some(msg) => {
                         let channel: String = msg.get_channel_name().to_string();
-                        if let Some(task_id) = channel.strip_prefix("task:cancel:") {
-                            tracing::info!(
-                                worker_id = %worker_id,
-                                task_id = %task_id,
-                                "Received cancellation command"
-                            );
-                            
-                            if let Err(e) = cancel_tx.send(task_id.to_string()).await {
-                                tracing::error!("Failed to send cancel message: {}", e);
-                            }
+                        let command = if let Some(task_id) = channel.strip_prefix("task:cancel:") {
+                            Some(TaskCommand::Cancel { task_id: task_id.to_string() })
+                        } else {
+                            msg.get_payload::<String>()
+                                .ok()
+                                .and_then(|payload| serde_json::from_str::<TaskCommand>(&payload).ok())
+                        };
+                        
+                        match command {
+                            Some(command) => route_command(&in_flight, &worker_id, command),
+                            None => tracing::warn!(channel = %channel, "Ignoring unreadable task command"),
                         }
                     }
                     None => {
@@ -100,89 +181,470 @@ impl WorkerEngine {
         });
     }
     
-    pub async fn run(mut self) -> Result<()> {
+    /// Spawne le reaper qui remet en queue les tâches dont la lease a expiré
+    fn spawn_reaper(&self) {
+        let conn = self.conn.clone();
+        let db = self.mongo_db.clone();
+        let worker_id = self.worker_id.clone();
+        let visibility_timeout = self.config.visibility_timeout;
+        let interval = self.config.reaper_interval;
+        
+        tokio::spawn(async move {
+            tracing::info!(worker_id = %worker_id, "Starting lease reaper");
+            
+            loop {
+                sleep(interval).await;
+                
+                match reap_expired_leases(conn.clone(), &db, visibility_timeout).await {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        tracing::warn!(
+                            worker_id = %worker_id,
+                            count = count,
+                            "Requeued tasks with expired lease"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::error!(worker_id = %worker_id, error = %e, "Lease reaper failed");
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Spawne le promoteur qui enfile les tâches différées arrivées à échéance
+    fn spawn_promoter(&self) {
+        let conn = self.conn.clone();
+        let db = self.mongo_db.clone();
+        let task_types: Vec<TaskType> = self.queues.iter().map(|binding| binding.task_type.clone()).collect();
+        let worker_id = self.worker_id.clone();
+        let interval = self.config.promoter_interval;
+        
+        tokio::spawn(async move {
+            tracing::info!(worker_id = %worker_id, "Starting scheduled task promoter");
+            
+            loop {
+                sleep(interval).await;
+                
+                for task_type in &task_types {
+                    match promote_due_tasks(conn.clone(), &db, task_type).await {
+                        Ok(0) => {}
+                        Ok(count) => {
+                            tracing::info!(
+                                worker_id = %worker_id,
+                                task_type = %task_type,
+                                count = count,
+                                "Promoted scheduled tasks"
+                            );
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                worker_id = %worker_id,
+                                task_type = %task_type,
+                                error = %e,
+                                "Scheduled task promoter failed"
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Spawne le sweeper qui expire les tâches encore en attente après leur `expires_at`
+    fn spawn_expiry_sweeper(&self) {
+        let conn = self.conn.clone();
+        let db = self.mongo_db.clone();
+        let pubsub = self.pubsub_client.clone();
+        let task_types: Vec<TaskType> = self.queues.iter().map(|binding| binding.task_type.clone()).collect();
+        let worker_id = self.worker_id.clone();
+        let interval = self.config.expiry_sweep_interval;
+        
+        tokio::spawn(async move {
+            tracing::info!(worker_id = %worker_id, "Starting expired task sweeper");
+            
+            loop {
+                sleep(interval).await;
+                
+                for task_type in &task_types {
+                    match expire_stale_tasks(conn.clone(), &db, &pubsub, task_type).await {
+                        Ok(0) => {}
+                        Ok(count) => {
+                            tracing::warn!(
+                                worker_id = %worker_id,
+                                task_type = %task_type,
+                                count = count,
+                                "Expired tasks that did not start in time"
+                            );
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                worker_id = %worker_id,
+                                task_type = %task_type,
+                                error = %e,
+                                "Expired task sweeper failed"
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Spawne le relais qui enfile les tâches restées dans l'outbox
+    fn spawn_outbox_relay(&self) {
+        let mut conn = self.conn.clone();
+        let db = self.mongo_db.clone();
+        let worker_id = self.worker_id.clone();
+        let interval = self.config.outbox_relay_interval;
+        let grace = self.config.outbox_grace;
+        let retry_policy = self.config.retry_policy.clone();
+        
+        tokio::spawn(async move {
+            tracing::info!(worker_id = %worker_id, "Starting outbox relay");
+            
+            loop {
+                sleep(interval).await;
+                
+                match outbox::relay_pending(&db, &mut conn, &retry_policy, grace).await {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        tracing::info!(worker_id = %worker_id, count = count, "Relayed outbox entries");
+                    }
+                    Err(e) => {
+                        tracing::error!(worker_id = %worker_id, error = %e, "Outbox relay failed");
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Spawne la réconciliation qui ré-enfile les tâches en attente absentes de Redis
+    fn spawn_reconciler(&self) {
+        let mut conn = self.conn.clone();
+        let db = self.mongo_db.clone();
+        let worker_id = self.worker_id.clone();
+        let interval = self.config.reconcile_interval;
+        let min_age = self.config.reconcile_min_age;
+        
+        tokio::spawn(async move {
+            tracing::info!(worker_id = %worker_id, "Starting queue reconciler");
+            
+            loop {
+                sleep(interval).await;
+                
+                match outbox::reconcile(&db, &mut conn, min_age).await {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        tracing::warn!(worker_id = %worker_id, count = count, "Re-enqueued tasks missing from Redis");
+                    }
+                    Err(e) => {
+                        tracing::error!(worker_id = %worker_id, error = %e, "Queue reconciler failed");
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Spawne la boucle de heartbeat qui rafraîchit la fiche du worker dans le registre
+    fn spawn_heartbeat(&self) {
+        let mut conn = self.conn.clone();
+        let info = self.info.clone();
+        let worker_id = self.worker_id.clone();
+        let interval = self.config.heartbeat_interval;
+        
+        tokio::spawn(async move {
+            loop {
+                sleep(interval).await;
+                
+                if let Err(e) = send_heartbeat(&mut conn, &info).await {
+                    tracing::error!(worker_id = %worker_id, error = %e, "Failed to send heartbeat");
+                }
+            }
+        });
+    }
+    
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
         tracing::info!(
             worker_id = %self.worker_id,
-            queue = %self.queue_name,
+            queues = %self.queues.iter().map(|binding| format!("{}:{}", binding.task_type, binding.weight)).collect::<Vec<_>>().join(","),
+            concurrency = self.config.concurrency,
             "Worker started"
         );
         
+        // S'enregistrer avant de prendre des tâches
+        let mut conn = self.conn.clone();
+        send_heartbeat(&mut conn, &self.info)
+            .await
+            .context("Failed to register worker")?;
+        
+        // Tâches laissées en processing par un worker disparu (panne, crash)
+        if let Err(e) = recover_orphaned_tasks(&self.mongo_db, &mut conn, &self.config).await {
+            tracing::error!(worker_id = %self.worker_id, error = %e, "Startup recovery failed");
+        }
+        
         // Spawner le listener global AVANT la boucle
         self.spawn_cancel_listener().await;
+        self.spawn_reaper();
+        self.spawn_promoter();
+        self.spawn_expiry_sweeper();
+        self.spawn_outbox_relay();
+        self.spawn_reconciler();
+        self.spawn_heartbeat();
         
         // Attendre un peu que le listener soit prêt
         sleep(Duration::from_millis(500)).await;
         
+        let engine = Arc::new(self);
+        let concurrency = engine.config.concurrency;
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        
         loop {
-            match self.process_next_task().await {
-                Ok(Some(())) => {
-                    tracing::debug!(worker_id = %self.worker_id, "Task processed successfully");
+            // Un slot libre d'abord : on ne sort de tâche de la queue que si on peut la traiter
+            let permit = tokio::select! {
+                _ = shutdown.changed() => break,
+                permit = semaphore.clone().acquire_owned() => permit?,
+            };
+            
+            // Le dequeue bloquant est interrompu dès que le shutdown est demandé
+            let dequeued = tokio::select! {
+                _ = shutdown.changed() => break,
+                result = engine.dequeue() => result,
+            };
+            
+            match dequeued {
+                Ok(Some((queue_index, payload))) => {
+                    let engine = engine.clone();
+                    
+                    tokio::spawn(async move {
+                        match engine.process_task(queue_index, payload).await {
+                            Ok(()) => {
+                                tracing::debug!(worker_id = %engine.worker_id, "Task processed successfully");
+                            }
+                            Err(e) => {
+                                tracing::error!(
+                                    worker_id = %engine.worker_id,
+                                    error = %e,
+                                    "Error processing task"
+                                );
+                            }
+                        }
+                        // Pas d'attente ici : le backoff des retries est porté par la tâche
+                        drop(permit);
+                    });
                 }
                 Ok(None) => {
-                    // Aucune tâche disponible, attendre
-                    tracing::trace!(worker_id = %self.worker_id, "No tasks available, waiting...");
-                    sleep(Duration::from_secs(2)).await;
+                    // Timeout du BLMOVE sans tâche, on repart en attente
+                    tracing::trace!(worker_id = %engine.worker_id, "No tasks available, waiting...");
                 }
                 Err(e) => {
                     tracing::error!(
-                        worker_id = %self.worker_id,
+                        worker_id = %engine.worker_id,
                         error = %e,
-                        "Error processing task"
+                        "Error dequeuing task"
                     );
                     sleep(Duration::from_secs(5)).await;
                 }
             }
         }
+        
+        // Drainer : laisser les tâches en cours finir pendant le délai de grâce
+        let grace = engine.config.shutdown_grace;
+        tracing::info!(
+            worker_id = %engine.worker_id,
+            grace_secs = grace.as_secs(),
+            "Stopped dequeuing, draining in-flight tasks"
+        );
+        
+        if tokio::time::timeout(grace, semaphore.acquire_many(concurrency as u32)).await.is_err() {
+            // Interrompre ce qui tourne encore ; on reboucle pour attraper les
+            // tâches qui s'inscrivent pendant l'interruption
+            loop {
+                let interrupted = engine.interrupt_in_flight();
+                if interrupted > 0 {
+                    tracing::warn!(
+                        worker_id = %engine.worker_id,
+                        count = interrupted,
+                        "Grace period expired, interrupting in-flight tasks"
+                    );
+                }
+                
+                let drained = tokio::time::timeout(
+                    Duration::from_secs(1),
+                    semaphore.acquire_many(concurrency as u32),
+                )
+                .await;
+                if drained.is_ok() {
+                    break;
+                }
+            }
+        }
+        
+        // Arrêt propre : le worker quitte le registre plutôt que d'y apparaître mort
+        let _: redis::RedisResult<()> = redis::cmd("HDEL")
+            .arg(queue::WORKERS_KEY)
+            .arg(&engine.worker_id)
+            .query_async(&mut conn)
+            .await;
+        
+        tracing::info!(worker_id = %engine.worker_id, "Worker stopped");
+        
+        Ok(())
     }
     
-    async fn process_next_task(&mut self) -> Result<Option<()>> {
-        // 1. Dequeue depuis Redis (RPOP = prendre depuis la fin)
-        let mut conn = self.redis_client.get_async_connection().await?;
+    /// Attend une tâche et la déplace atomiquement vers la liste de traitement.
+    ///
+    /// Les lanes sont d'abord consultées directement ; si elles sont vides, le
+    /// worker bloque sur les listes de notification jusqu'à l'arrivée d'un jeton.
+    /// Renvoie l'index de la queue d'origine avec le payload.
+    async fn dequeue(&self) -> Result<Option<(usize, String)>> {
+        if let Some(dequeued) = self.try_dequeue(true).await? {
+            return Ok(Some(dequeued));
+        }
         
-        let task_json: Option<String> = redis::cmd("RPOP")
-            .arg(&self.queue_name)
-            .query_async(&mut conn)
+        let mut wait = redis::cmd("BRPOP");
+        for binding in &self.queues {
+            wait.arg(queue::notify_key(&binding.task_type));
+        }
+        let token: Option<(String, String)> = wait
+            .arg(self.config.block_timeout.as_secs_f64())
+            .query_async(&mut self.blocking_conn.clone())
+            .await
+            .context("Failed to wait for task notification")?;
+        
+        if token.is_none() {
+            return Ok(None);
+        }
+        
+        // Le jeton vient d'être consommé par le BRPOP
+        self.try_dequeue(false).await
+    }
+    
+    /// Dequeue non bloquant sur les lanes de toutes les queues servies.
+    ///
+    /// La queue élue par le cycle pondéré des types passe en tête ; l'ordre des
+    /// lanes avance d'un cran à chaque cycle complet des types, pour que chaque
+    /// queue voie toutes les lanes mener à tour de rôle.
+    async fn try_dequeue(&self, consume_token: bool) -> Result<Option<(usize, String)>> {
+        let count = self.dequeue_count.fetch_add(1, Ordering::Relaxed);
+        let weights: Vec<u64> = self.queues.iter().map(|binding| binding.weight).collect();
+        let type_cycle: u64 = weights.iter().sum::<u64>().max(1);
+        let lanes = queue::lane_order(count / type_cycle);
+        
+        let mut invocation = self.dequeue_script.prepare_invoke();
+        invocation
+            .key(&self.processing_key)
+            .key(queue::LEASES_KEY)
+            .key(queue::PROCESSING_LISTS_KEY);
+        for index in queue::weighted_order(&weights, count) {
+            let task_type = &self.queues[index].task_type;
+            for priority in &lanes {
+                invocation
+                    .key(queue::lane_key(task_type, *priority))
+                    .key(queue::notify_key(task_type));
+            }
+        }
+        
+        let dequeued: Option<(String, String)> = invocation
+            .arg(if consume_token { "1" } else { "0" })
+            .arg(lease_deadline(self.config.visibility_timeout))
+            .invoke_async(&mut self.conn.clone())
             .await
             .context("Failed to dequeue task from Redis")?;
         
-        let task_json = match task_json {
-            Some(json) => json,
-            None => return Ok(None), // Pas de tâche
-        };
+        Ok(dequeued.and_then(|(lane, payload)| {
+            self.queues
+                .iter()
+                .position(|binding| queue::lane_keys(&binding.task_type).contains(&lane))
+                .map(|index| (index, payload))
+        }))
+    }
+    
+    async fn process_task(&self, queue_index: usize, payload: String) -> Result<()> {
+        let binding = &self.queues[queue_index];
         
-        // 2. Désérialiser la tâche
-        let mut task: Task = serde_json::from_str(&task_json)
-            .context("Failed to deserialize task")?;
+        // 1. Lire l'identifiant de la tâche
+        let Some(task_id) = queue::entry_task_id(&payload) else {
+            tracing::error!(
+                worker_id = %self.worker_id,
+                "Invalid queue entry, moving it to dead-letter queue"
+            );
+            let entry = DeadLetter::from_invalid_payload(binding.task_type.clone(), payload.clone(), "missing task_id".to_string());
+            self.dead_letter(&payload, &entry).await?;
+            return Ok(());
+        };
         
         tracing::info!(
             worker_id = %self.worker_id,
-            task_id = %task.id,
+            task_id = %task_id,
             "Dequeued task"
         );
         
-        // 3. Vérifier le statut dans MongoDB AVANT de traiter
-        let db_task = self.get_task_from_db(&task.id).await?;
+        // Inscrite avant la lecture Mongo : une commande publiée après la
+        // mise à jour du statut ne peut plus être perdue
+        let (cancel_trigger, cancel_rx) = oneshot::channel();
+        let pause_flag: PauseFlag = Arc::new(AtomicBool::new(false));
+        let _in_flight = InFlightGuard::register(self, &task_id, cancel_trigger, pause_flag.clone());
         
-        match db_task {
-            Some(db_task) if db_task.status == TaskStatus::Cancelled => {
-                tracing::warn!(
+        // 2. Charger la tâche depuis MongoDB, qui fait foi, AVANT de traiter
+        let mut task = match self.get_task_from_db(&task_id).await? {
+            Some(task) => task,
+            None => {
+                tracing::error!(
                     worker_id = %self.worker_id,
-                    task_id = %task.id,
-                    "Task was cancelled, skipping"
+                    task_id = %task_id,
+                    "Task not found in database, skipping"
                 );
-                return Ok(Some(()));
+                self.ack_task(&payload, &task_id).await?;
+                return Ok(());
             }
-            None => {
-                tracing::error!(
+        };
+        
+        match task.status {
+            TaskStatus::Paused => {
+                // Mise en pause pendant le dequeue : on la retient sans la commencer
+                tracing::info!(
                     worker_id = %self.worker_id,
                     task_id = %task.id,
-                    "Task not found in database, skipping"
+                    "Task was paused, holding it back"
                 );
-                return Ok(Some(()));
+                self.park_task(&payload, &task).await?;
+                return Ok(());
             }
-            _ => {
-                // Task is valid, proceed
+            TaskStatus::Cancelling => {
+                // Annulée alors qu'elle quittait la queue
+                return self.settle_conflict(&payload, &task.id).await;
             }
+            ref status if status.is_terminal() => {
+                tracing::warn!(
+                    worker_id = %self.worker_id,
+                    task_id = %task.id,
+                    status = %status,
+                    "Task already finished, skipping"
+                );
+                self.ack_task(&payload, &task.id).await?;
+                return Ok(());
+            }
+            _ => {}
+        }
+        
+        // Trop tard pour commencer : abandonnée sans être traitée
+        if task.is_expired(chrono::Utc::now()) && task.status.can_transition_to(&TaskStatus::Expired) {
+            return self.expire_task(&payload, task).await;
+        }
+        
+        // Une tâche tout juste promue peut encore apparaître scheduled ou retrying
+        let stored_status = task.status.clone();
+        if matches!(stored_status, TaskStatus::Scheduled | TaskStatus::Retrying) {
+            task.update_status(TaskStatus::Queued)?;
+        }
+        task.assign_worker(&self.worker_id);
+        task.update_status(TaskStatus::Processing)?;
+        
+        let filter = status_filter(&task.id, &[stored_status]);
+        let started = self.save_task(&mut task, filter).await?;
+        if !started {
+            return self.settle_conflict(&payload, &task.id).await;
         }
         
         tracing::info!(
@@ -190,100 +652,341 @@ impl WorkerEngine {
             task_id = %task.id,
             "Processing task"
         );
+        self.emit(TaskEvent::started(&task, &self.worker_id)).await;
         
-        // 4. Créer le callback de progression
-        let task_id = task.id.clone();
-        let db = self.mongo_db.clone();
+        // 3. Créer le callback de progression : il ne fait que déposer la
+        // dernière valeur, diffusée (throttlée) par un reporter dédié
+        let (progress_tx, progress_rx) = watch::channel(task.progress);
         let progress_callback: ProgressCallback = Arc::new(move |progress| {
-            let task_id = task_id.clone();
-            let db = db.clone();
-            
-            // Spawner une tâche async pour update MongoDB
-            tokio::spawn(async move {
-                if let Err(e) = update_task_progress(&db, &task_id, progress).await {
-                    tracing::error!(
-                        task_id = %task_id,
-                        progress = progress,
-                        error = %e,
-                        "Failed to update progress in MongoDB"
-                    );
-                }
-            });
+            let _ = progress_tx.send(progress);
         });
+        let reporter = tokio::spawn(report_progress(
+            self.pubsub_client.clone(),
+            self.mongo_db.clone(),
+            task.id.clone(),
+            progress_rx,
+            self.config.clone(),
+        ));
         
-        // 5. Créer le cancel_flag
+        // 4. Créer le cancel_flag (annulation ou timeout)
         let cancel_flag = Arc::new(AtomicBool::new(false));
         let cancel_flag_clone = cancel_flag.clone();
         
-        // 6. Traiter avec tokio::select!
+        // 5. Traiter avec tokio::select! en renouvelant la lease en parallèle,
+        // dans la limite du timeout de la tâche
         let task_id_for_select = task.id.clone();
-        let process_future = self.processor.process(&mut task, progress_callback, cancel_flag_clone);
-        
-        tokio::select! {
-            result = process_future => {
-                // Traitement terminé normalement
-                match result {
-                    Ok(()) => {
-                        self.update_task_in_db(&task).await?;
-                        tracing::info!(
-                            worker_id = %self.worker_id,
-                            task_id = %task.id,
-                            "Task completed successfully"
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!(
+        let timeout = task.timeout();
+        let started_task = task.clone();
+        let mut timed_out = false;
+        let outcome = {
+            let process_future = binding.processor.process(&mut task, progress_callback, cancel_flag_clone, pause_flag.clone());
+            let renew_future = renew_lease(
+                self.conn.clone(),
+                &task_id_for_select,
+                self.config.visibility_timeout,
+            );
+            let deadline = sleep(timeout);
+            // Le temps passé en pause ne compte pas dans le timeout
+            let mut pause_tick = tokio::time::interval(PAUSE_TICK);
+            // Fusionné : jamais repollé une fois résolu, même sans ordre d'arrêt
+            let mut cancel_rx = cancel_rx.fuse();
+            tokio::pin!(process_future);
+            tokio::pin!(renew_future);
+            tokio::pin!(deadline);
+            
+            loop {
+                tokio::select! {
+                    result = &mut process_future => break ProcessOutcome::Finished(result),
+                    Ok(reason) = &mut cancel_rx => match reason {
+                        StopReason::Cancelled => break ProcessOutcome::Cancelled,
+                        StopReason::Shutdown => break ProcessOutcome::Interrupted,
+                    },
+                    _ = &mut deadline => {
+                        tracing::warn!(
                             worker_id = %self.worker_id,
-                            task_id = %task.id,
-                            error = %e,
-                            "Task processing failed"
+                            task_id = %task_id_for_select,
+                            timeout_secs = timeout.as_secs(),
+                            "Task timed out, flagging it for cancellation"
                         );
+                        cancel_flag.store(true, Ordering::SeqCst);
                         
-                        task.increment_retry();
-                        task.error = Some(e.to_string());
-                        
-                        if task.should_retry() {
-                            task.update_status(TaskStatus::Pending);
-                            self.requeue_task(&task).await?;
+                        // Le processor a un délai de grâce pour s'arrêter, sinon il est abandonné
+                        if tokio::time::timeout(self.config.timeout_grace, &mut process_future).await.is_err() {
                             tracing::warn!(
                                 worker_id = %self.worker_id,
-                                task_id = %task.id,
-                                retry_count = task.retry_count,
-                                "Task requeued for retry"
-                            );
-                        } else {
-                            task.update_status(TaskStatus::Failed);
-                            self.update_task_in_db(&task).await?;
-                            tracing::error!(
-                                worker_id = %self.worker_id,
-                                task_id = %task.id,
-                                "Task failed permanently after max retries"
+                                task_id = %task_id_for_select,
+                                "Processor ignored the cancel flag, dropping it"
                             );
                         }
+                        timed_out = true;
+                        break ProcessOutcome::Finished(Err(anyhow::anyhow!("Task timed out after {}s", timeout.as_secs())));
+                    }
+                    _ = pause_tick.tick() => {
+                        if pause_flag.load(Ordering::SeqCst) {
+                            let extended = deadline.deadline() + PAUSE_TICK;
+                            deadline.as_mut().reset(extended);
+                        }
                     }
+                    _ = &mut renew_future => {}
+                }
+            }
+        };
+        
+        // Le callback est libéré avec le processor : le reporter publie la
+        // dernière valeur et se termine avant l'écriture de l'état final
+        if let Err(e) = reporter.await {
+            tracing::error!(worker_id = %self.worker_id, task_id = %task.id, error = %e, "Progress reporter panicked");
+        }
+        
+        // Une tentative expirée est un échec comme un autre, donc retentée :
+        // on repart de la tâche telle qu'elle était au démarrage, le processor
+        // ayant pu la marquer annulée en honorant le cancel_flag
+        if timed_out {
+            task = started_task;
+        }
+        
+        // Le processor a pu honorer le cancel_flag avant qu'on interrompe le future
+        let outcome = match outcome {
+            ProcessOutcome::Finished(Err(_)) if task.status == TaskStatus::Cancelled => ProcessOutcome::Cancelled,
+            outcome => outcome,
+        };
+        
+        match outcome {
+            ProcessOutcome::Finished(Ok(())) => {
+                self.record_result(true);
+                task.update_status(TaskStatus::Completed)?;
+                if !self.save_transition(&mut task).await? {
+                    return self.settle_conflict(&payload, &task.id).await;
                 }
+                self.ack_task(&payload, &task.id).await?;
+                tracing::info!(
+                    worker_id = %self.worker_id,
+                    task_id = %task.id,
+                    "Task completed successfully"
+                );
+                self.emit(TaskEvent::completed(&task.id)).await;
+                self.settle_dependents(&task.id).await;
             }
-            Some(cancelled_task_id) = self.cancel_rx.recv() => {
-                if cancelled_task_id == task_id_for_select {
-                    // Marquer comme cancelled
-                    cancel_flag.store(true, Ordering::SeqCst);
+            ProcessOutcome::Finished(Err(e)) => {
+                tracing::error!(
+                    worker_id = %self.worker_id,
+                    task_id = %task.id,
+                    error = %e,
+                    "Task processing failed"
+                );
+                self.record_result(false);
+                
+                task.increment_retry();
+                task.record_error(e.to_string());
+                
+                if task.should_retry() {
+                    let delay = self.config.retry_policy.next_delay(task.retry_count);
+                    let retry_at = chrono::Utc::now() + chrono::Duration::from_std(delay)?;
+                    
+                    task.next_retry_at = Some(retry_at);
+                    task.update_status(TaskStatus::Retrying)?;
+                    if !self.save_transition(&mut task).await? {
+                        return self.settle_conflict(&payload, &task.id).await;
+                    }
+                    self.requeue_task(&payload, &task).await?;
                     tracing::warn!(
                         worker_id = %self.worker_id,
                         task_id = %task.id,
-                        "Cancellation signal received, flagging task"
+                        retry_count = task.retry_count,
+                        retry_at = %retry_at,
+                        "Task scheduled for retry"
                     );
-                    
-                    // Attendre un peu que le processor détecte la cancellation
-                    sleep(Duration::from_millis(500)).await;
-                    
-                    // Mettre à jour MongoDB
-                    task.update_status(TaskStatus::Cancelled);
-                    self.update_task_in_db(&task).await?;
+                    self.emit(TaskEvent::retried(&task)).await;
+                } else {
+                    task.update_status(TaskStatus::Failed)?;
+                    if !self.save_transition(&mut task).await? {
+                        return self.settle_conflict(&payload, &task.id).await;
+                    }
+                    self.dead_letter(&payload, &DeadLetter::from_failed_task(&task, payload.clone())).await?;
+                    tracing::error!(
+                        worker_id = %self.worker_id,
+                        task_id = %task.id,
+                        "Task failed permanently after max retries, moved to dead-letter queue"
+                    );
+                    self.emit(TaskEvent::failed(&task)).await;
+                    self.settle_dependents(&task.id).await;
                 }
             }
+            ProcessOutcome::Cancelled => {
+                // Marquer comme cancelled
+                cancel_flag.store(true, Ordering::SeqCst);
+                tracing::warn!(
+                    worker_id = %self.worker_id,
+                    task_id = %task.id,
+                    "Cancellation signal received, flagging task"
+                );
+                
+                // Attendre un peu que le processor détecte la cancellation
+                sleep(Duration::from_millis(500)).await;
+                
+                // Mettre à jour MongoDB
+                task.update_status(TaskStatus::Cancelled)?;
+                if !self.save_transition(&mut task).await? {
+                    return self.settle_conflict(&payload, &task.id).await;
+                }
+                self.ack_task(&payload, &task.id).await?;
+                self.emit(TaskEvent::cancelled(&task.id)).await;
+                self.settle_dependents(&task.id).await;
+            }
+            ProcessOutcome::Interrupted => {
+                // Marquer comme annulée pour cause d'arrêt
+                cancel_flag.store(true, Ordering::SeqCst);
+                tracing::warn!(
+                    worker_id = %self.worker_id,
+                    task_id = %task.id,
+                    "Worker shutting down, interrupting task"
+                );
+                
+                sleep(Duration::from_millis(500)).await;
+                
+                // L'interruption n'est pas un échec : retry_count inchangé
+                task.progress = 0.0;
+                task.next_retry_at = None;
+                
+                // Une tâche en pause reste retenue jusqu'à sa reprise ; le
+                // motif distingue cette remise en attente d'un enqueue normal
+                let paused = pause_flag.load(Ordering::SeqCst);
+                task.update_status_with_reason(
+                    if paused { TaskStatus::Paused } else { TaskStatus::Queued },
+                    INTERRUPTED_BY_SHUTDOWN,
+                )?;
+                if !self.save_transition(&mut task).await? {
+                    return self.settle_conflict(&payload, &task.id).await;
+                }
+                
+                if paused {
+                    self.park_task(&payload, &task).await?;
+                } else {
+                    self.requeue_interrupted_task(&payload, &task).await?;
+                    self.emit(TaskEvent::interrupted(&task.id, INTERRUPTED_BY_SHUTDOWN)).await;
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Résout une écriture de statut refusée : la tâche a changé entre-temps
+    /// (typiquement une annulation). Le payload est acquitté dans tous les cas.
+    async fn settle_conflict(&self, payload: &str, task_id: &str) -> Result<()> {
+        if let Some(mut stored) = self.get_task_from_db(task_id).await? {
+            if stored.status == TaskStatus::Cancelling {
+                let cancelling = stored.status.clone();
+                stored.update_status(TaskStatus::Cancelled)?;
+                if self.save_task(&mut stored, status_filter(task_id, &[cancelling])).await? {
+                    self.emit(TaskEvent::cancelled(task_id)).await;
+                    self.settle_dependents(task_id).await;
+                }
+            }
+            
+            tracing::warn!(
+                worker_id = %self.worker_id,
+                task_id = %task_id,
+                status = %stored.status,
+                "Task status changed concurrently, dropping it"
+            );
+        }
+        
+        self.ack_task(payload, task_id).await
+    }
+    
+    /// Publie un événement de cycle de vie. Une erreur est seulement
+    /// journalisée : les abonnés sont des observateurs.
+    async fn emit(&self, event: TaskEvent) {
+        if let Err(e) = self.pubsub_client.publish_event(&event).await {
+            tracing::warn!(
+                worker_id = %self.worker_id,
+                task_id = %event.task_id(),
+                event = %event.kind(),
+                error = %e,
+                "Failed to publish task event"
+            );
+        }
+    }
+    
+    /// Débloque ou fait échouer les tâches qui dépendent d'une tâche terminée.
+    /// Une erreur est seulement journalisée : la tâche elle-même est réglée.
+    async fn settle_dependents(&self, task_id: &str) {
+        let mut conn = self.conn.clone();
+        if let Err(e) = dependencies::settle_dependents(&self.mongo_db, &mut conn, task_id).await {
+            tracing::error!(
+                worker_id = %self.worker_id,
+                task_id = %task_id,
+                error = %e,
+                "Failed to settle dependent tasks"
+            );
+        }
+    }
+    
+    /// Passe en `expired` une tâche sortie de la queue après son échéance
+    async fn expire_task(&self, payload: &str, mut task: Task) -> Result<()> {
+        let stored_status = task.status.clone();
+        task.update_status(TaskStatus::Expired)?;
+        
+        let filter = status_filter(&task.id, &[stored_status]);
+        if !self.save_task(&mut task, filter).await? {
+            return self.settle_conflict(payload, &task.id).await;
         }
+        self.ack_task(payload, &task.id).await?;
+        self.emit(TaskEvent::expired(&task.id)).await;
         
-        Ok(Some(()))
+        tracing::warn!(
+            worker_id = %self.worker_id,
+            task_id = %task.id,
+            expires_at = ?task.expires_at,
+            "Task expired before it could start, skipping"
+        );
+        self.settle_dependents(&task.id).await;
+        
+        Ok(())
+    }
+    
+    /// Déclenche l'arrêt de toutes les tâches en cours ; renvoie leur nombre
+    fn interrupt_in_flight(&self) -> usize {
+        let triggers: Vec<_> = match self.in_flight.lock() {
+            Ok(mut tasks) => tasks.drain().flat_map(|(_, controls)| controls).map(|control| control.stop).collect(),
+            Err(_) => return 0,
+        };
+        
+        let count = triggers.len();
+        for trigger in triggers {
+            let _ = trigger.send(StopReason::Shutdown);
+        }
+        
+        count
+    }
+    
+    /// Comptabilise une tentative terminée dans les compteurs du registre
+    fn record_result(&self, success: bool) {
+        if let Ok(mut info) = self.info.lock() {
+            if success {
+                info.processed_count += 1;
+            } else {
+                info.failed_count += 1;
+            }
+        }
+    }
+    
+    /// Acquitte une tâche : la retire de la liste de traitement et libère sa lease
+    async fn ack_task(&self, payload: &str, task_id: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        
+        let _: () = redis::pipe()
+            .atomic()
+            .lrem(&self.processing_key, 1, payload)
+            .ignore()
+            .zrem(queue::LEASES_KEY, task_id)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .context("Failed to acknowledge task")?;
+        
+        Ok(())
     }
     
     async fn get_task_from_db(&self, task_id: &str) -> Result<Option<Task>> {
@@ -298,29 +1001,105 @@ impl WorkerEngine {
         Ok(task)
     }
     
-    async fn update_task_in_db(&self, task: &Task) -> Result<()> {
+    /// Écrit la tâche si son statut stocké peut encore mener au statut courant
+    async fn save_transition(&self, task: &mut Task) -> Result<bool> {
+        let filter = transition_filter(&task.id, &task.status);
+        self.save_task(task, filter).await
+    }
+    
+    /// Écrit la tâche si le statut stocké correspond à `filter` ; renvoie
+    /// faux si la tâche a changé de statut entre-temps
+    async fn save_task(&self, task: &mut Task, filter: mongodb::bson::Document) -> Result<bool> {
         let collection = self.mongo_db.collection::<Task>("tasks");
         
-        let filter = mongodb::bson::doc! { "task_id": &task.id };
-        let update = mongodb::bson::doc! {
-            "$set": mongodb::bson::to_document(task)?
-        };
-        
-        collection
-            .update_one(filter, update, None)
+        let result = collection
+            .update_one(filter, task_update(task)?, None)
             .await
             .context("Failed to update task in MongoDB")?;
         
+        let saved = result.matched_count > 0;
+        if saved {
+            task.mark_transitions_saved();
+        }
+        
+        Ok(saved)
+    }
+    
+    /// Range l'entrée dans la dead-letter queue et acquitte le payload dans la même transaction
+    async fn dead_letter(&self, payload: &str, entry: &DeadLetter) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let serialized = serde_json::to_string(entry)?;
+        
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hset(queue::dead_letter_key(&entry.task_type), &entry.id, serialized)
+            .ignore()
+            .lrem(&self.processing_key, 1, payload)
+            .ignore();
+        if entry.task.is_some() {
+            pipe.zrem(queue::LEASES_KEY, &entry.id).ignore();
+        }
+        
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .context("Failed to move task to dead-letter queue")?;
+        
+        Ok(())
+    }
+    
+    /// Retient une tâche en pause hors des queues et l'acquitte dans la même transaction
+    async fn park_task(&self, payload: &str, task: &Task) -> Result<()> {
+        let mut conn = self.conn.clone();
+        
+        let _: () = redis::pipe()
+            .atomic()
+            .hset(queue::paused_key(&task.task_type), &task.id, &task.id)
+            .ignore()
+            .lrem(&self.processing_key, 1, payload)
+            .ignore()
+            .zrem(queue::LEASES_KEY, &task.id)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .context("Failed to hold paused task back")?;
+        
         Ok(())
     }
     
-    async fn requeue_task(&self, task: &Task) -> Result<()> {
-        let mut conn = self.redis_client.get_async_connection().await?;
-        let serialized = serde_json::to_string(task)?;
+    /// Remet une tâche interrompue en tête de sa lane et l'acquitte dans la même transaction
+    async fn requeue_interrupted_task(&self, payload: &str, task: &Task) -> Result<()> {
+        let mut conn = self.conn.clone();
         
-        let _: () = redis::cmd("LPUSH")
-            .arg(&self.queue_name)
-            .arg(serialized)
+        let mut pipe = redis::pipe();
+        queue::push_task_front(pipe.atomic(), task);
+        let _: () = pipe
+            .lrem(&self.processing_key, 1, payload)
+            .ignore()
+            .zrem(queue::LEASES_KEY, &task.id)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .context("Failed to requeue interrupted task")?;
+        
+        Ok(())
+    }
+    
+    /// Remet la tâche en queue (ou en attente jusqu'à `next_retry_at`) et
+    /// l'acquitte dans la même transaction
+    async fn requeue_task(&self, payload: &str, task: &Task) -> Result<()> {
+        let mut conn = self.conn.clone();
+        
+        let mut pipe = redis::pipe();
+        match task.next_retry_at {
+            Some(retry_at) => queue::schedule_task(pipe.atomic(), task, retry_at.timestamp_millis()),
+            None => queue::push_task(pipe.atomic(), task),
+        }
+        let _: () = pipe
+            .lrem(&self.processing_key, 1, payload)
+            .ignore()
+            .zrem(queue::LEASES_KEY, &task.id)
+            .ignore()
             .query_async(&mut conn)
             .await?;
         
@@ -328,14 +1107,514 @@ impl WorkerEngine {
     }
 }
 
-/// Fonction helper pour mettre à jour la progression dans MongoDB
+/// Applique une commande reçue à la tâche concernée, si elle tourne dans ce process
+fn route_command(in_flight: &InFlightTasks, worker_id: &str, command: TaskCommand) {
+    let mut tasks = match in_flight.lock() {
+        Ok(tasks) => tasks,
+        Err(_) => return,
+    };
+    let task_id = command.task_id().to_string();
+    
+    match command {
+        TaskCommand::Cancel { .. } => {
+            if let Some(controls) = tasks.remove(&task_id) {
+                tracing::info!(worker_id = %worker_id, task_id = %task_id, "Received cancellation command");
+                for control in controls {
+                    let _ = control.stop.send(StopReason::Cancelled);
+                }
+            }
+        }
+        TaskCommand::Pause { .. } => {
+            if let Some(controls) = tasks.get(&task_id) {
+                tracing::info!(worker_id = %worker_id, task_id = %task_id, "Received pause command");
+                for control in controls {
+                    control.pause_flag.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+        TaskCommand::Resume { .. } => {
+            if let Some(controls) = tasks.get(&task_id) {
+                tracing::info!(worker_id = %worker_id, task_id = %task_id, "Received resume command");
+                for control in controls {
+                    control.pause_flag.store(false, Ordering::SeqCst);
+                }
+            }
+        }
+    }
+}
+
+/// Inscrit une tâche comme en cours (annulation et registre) le temps de son traitement
+struct InFlightGuard<'a> {
+    engine: &'a WorkerEngine,
+    task_id: String,
+    token: u64,
+}
+
+impl<'a> InFlightGuard<'a> {
+    fn register(
+        engine: &'a WorkerEngine,
+        task_id: &str,
+        stop: oneshot::Sender<StopReason>,
+        pause_flag: PauseFlag,
+    ) -> Self {
+        let token = NEXT_IN_FLIGHT_TOKEN.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut tasks) = engine.in_flight.lock() {
+            tasks
+                .entry(task_id.to_string())
+                .or_default()
+                .push(InFlightControl { token, stop, pause_flag });
+        }
+        if let Ok(mut info) = engine.info.lock() {
+            info.current_task_ids.push(task_id.to_string());
+        }
+        
+        Self {
+            engine,
+            task_id: task_id.to_string(),
+            token,
+        }
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        // Ne retirer que cette livraison : une autre peut être en cours
+        if let Ok(mut tasks) = self.engine.in_flight.lock() {
+            if let Some(controls) = tasks.get_mut(&self.task_id) {
+                controls.retain(|control| control.token != self.token);
+                if controls.is_empty() {
+                    tasks.remove(&self.task_id);
+                }
+            }
+        }
+        if let Ok(mut info) = self.engine.info.lock() {
+            if let Some(position) = info.current_task_ids.iter().position(|id| id == &self.task_id) {
+                info.current_task_ids.remove(position);
+            }
+        }
+    }
+}
+
+/// Publie la fiche du worker dans le registre avec un horodatage frais
+async fn send_heartbeat(conn: &mut ConnectionManager, info: &Mutex<WorkerInfo>) -> Result<()> {
+    let (worker_id, serialized) = {
+        let mut info = info
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Worker info lock poisoned"))?;
+        info.last_heartbeat = chrono::Utc::now();
+        (info.worker_id.clone(), serde_json::to_string(&*info)?)
+    };
+    
+    let _: () = redis::cmd("HSET")
+        .arg(queue::WORKERS_KEY)
+        .arg(worker_id)
+        .arg(serialized)
+        .query_async(conn)
+        .await
+        .context("Failed to write worker heartbeat")?;
+    
+    Ok(())
+}
+
+/// Nom de la machine hôte, sans dépendance supplémentaire
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Échéance d'une lease posée maintenant (ms epoch)
+fn lease_deadline(visibility_timeout: Duration) -> i64 {
+    chrono::Utc::now().timestamp_millis() + visibility_timeout.as_millis() as i64
+}
+
+/// Prolonge périodiquement la lease d'une tâche en cours ; ne se termine jamais
+async fn renew_lease(mut conn: ConnectionManager, task_id: &str, visibility_timeout: Duration) {
+    let period = visibility_timeout / 3;
+    
+    loop {
+        sleep(period).await;
+        
+        let result: redis::RedisResult<()> = redis::cmd("ZADD")
+            .arg(queue::LEASES_KEY)
+            .arg("XX")
+            .arg(lease_deadline(visibility_timeout))
+            .arg(task_id)
+            .query_async(&mut conn)
+            .await;
+        
+        if let Err(e) = result {
+            tracing::error!(task_id = %task_id, error = %e, "Failed to renew task lease");
+        }
+    }
+}
+
+/// Enfile les tâches différées (planifiées ou en attente de retry) dont
+/// l'échéance est passée
+async fn promote_due_tasks(mut conn: ConnectionManager, db: &Database, task_type: &TaskType) -> Result<usize> {
+    let scheduled_key = queue::scheduled_key(task_type);
+    let now = chrono::Utc::now().timestamp_millis();
+    let promote_script = queue::promote_script();
+    let collection = db.collection::<Task>("tasks");
+    let mut promoted = 0;
+    
+    let due: Vec<String> = redis::cmd("ZRANGEBYSCORE")
+        .arg(&scheduled_key)
+        .arg("-inf")
+        .arg(now)
+        .arg("LIMIT")
+        .arg(0)
+        .arg(100)
+        .query_async(&mut conn)
+        .await?;
+    
+    for member in due {
+        // Un membre illisible part en dead-letter
+        let Some(task_id) = queue::entry_task_id(&member) else {
+            tracing::error!("Invalid scheduled entry, moving it to dead-letter queue");
+            let entry = DeadLetter::from_invalid_payload(task_type.clone(), member.clone(), "missing task_id".to_string());
+            let _: () = redis::pipe()
+                .atomic()
+                .zrem(&scheduled_key, &member)
+                .ignore()
+                .hset(queue::dead_letter_key(task_type), &entry.id, serde_json::to_string(&entry)?)
+                .ignore()
+                .query_async(&mut conn)
+                .await?;
+            continue;
+        };
+        
+        // MongoDB fait foi : une tâche supprimée ou qui n'attend plus est oubliée
+        let stored = collection
+            .find_one(mongodb::bson::doc! { "task_id": &task_id }, None)
+            .await
+            .context("Failed to load scheduled task from MongoDB")?;
+        let task = stored.and_then(|mut task| {
+            task.next_retry_at = None;
+            task.update_status(TaskStatus::Queued).ok()?;
+            Some(task)
+        });
+        let Some(task) = task else {
+            tracing::warn!(task_id = %task_id, "Scheduled task is gone or no longer waiting, dropping it");
+            let _: i32 = redis::cmd("ZREM")
+                .arg(&scheduled_key)
+                .arg(&member)
+                .query_async(&mut conn)
+                .await?;
+            continue;
+        };
+        
+        let moved: i32 = promote_script
+            .key(&scheduled_key)
+            .key(queue::lane_key(&task.task_type, task.priority))
+            .key(queue::notify_key(&task.task_type))
+            .arg(&member)
+            .arg(&task.id)
+            .invoke_async(&mut conn)
+            .await?;
+        
+        if moved == 0 {
+            // Promue par un autre worker ou annulée entre-temps
+            continue;
+        }
+        
+        // Ne pas écraser une annulation arrivée pendant la promotion
+        let filter = status_filter(
+            &task.id,
+            &[TaskStatus::Scheduled, TaskStatus::Retrying, TaskStatus::Pending],
+        );
+        collection
+            .update_one(filter, status_update(&task)?, None)
+            .await
+            .context("Failed to mark promoted task as queued")?;
+        
+        tracing::debug!(task_id = %task.id, "Scheduled task promoted to queue");
+        promoted += 1;
+    }
+    
+    Ok(promoted)
+}
+
+/// Passe en `expired` les tâches d'un type encore en attente après leur
+/// `expires_at`. Celles qui sont dans une lane ou en attente de promotion y
+/// restent : le worker qui les sortira les trouvera terminées et les acquittera.
+async fn expire_stale_tasks(
+    mut conn: ConnectionManager,
+    db: &Database,
+    pubsub: &PubSubClient,
+    task_type: &TaskType,
+) -> Result<usize> {
+    let collection = db.collection::<Task>("tasks");
+    let now = chrono::Utc::now();
+    let waiting: Vec<String> = TaskStatus::Expired
+        .predecessors()
+        .into_iter()
+        .filter(|status| *status != TaskStatus::Expired)
+        .map(|status| status.to_string())
+        .collect();
+    
+    let filter = mongodb::bson::doc! {
+        "task_type": mongodb::bson::to_bson(task_type)?,
+        "status": { "$in": waiting },
+        "expires_at": { "$lte": mongodb::bson::to_bson(&now)? },
+    };
+    let options = mongodb::options::FindOptions::builder().limit(100).build();
+    
+    let mut stale = Vec::new();
+    let mut cursor = collection.find(filter, options).await?;
+    while cursor.advance().await? {
+        stale.push(cursor.deserialize_current()?);
+    }
+    
+    let mut expired = 0;
+    // Les dates sont comparées sous forme de texte : on revérifie l'échéance
+    for mut task in stale.into_iter().filter(|task: &Task| task.is_expired(now)) {
+        let stored_status = task.status.clone();
+        task.update_status(TaskStatus::Expired)?;
+        
+        // Ne pas écraser une transition arrivée entre-temps
+        let result = collection
+            .update_one(status_filter(&task.id, std::slice::from_ref(&stored_status)), status_update(&task)?, None)
+            .await
+            .context("Failed to mark task as expired")?;
+        if result.matched_count == 0 {
+            continue;
+        }
+        
+        // Une tâche en pause est retenue hors des queues, par id
+        if stored_status == TaskStatus::Paused {
+            let _: () = redis::cmd("HDEL")
+                .arg(queue::paused_key(task_type))
+                .arg(&task.id)
+                .query_async(&mut conn)
+                .await?;
+        }
+        
+        if let Err(e) = pubsub.publish_event(&TaskEvent::expired(&task.id)).await {
+            tracing::warn!(task_id = %task.id, error = %e, "Failed to publish task event");
+        }
+        
+        if let Err(e) = dependencies::settle_dependents(db, &mut conn, &task.id).await {
+            tracing::error!(task_id = %task.id, error = %e, "Failed to settle dependents of expired task");
+        }
+        
+        tracing::debug!(task_id = %task.id, status = %stored_status, "Task expired while waiting");
+        expired += 1;
+    }
+    
+    Ok(expired)
+}
+
+/// Parcourt toutes les listes de traitement et remet en queue les tâches
+/// dont la lease a expiré (worker crashé ou bloqué)
+async fn reap_expired_leases(mut conn: ConnectionManager, db: &Database, visibility_timeout: Duration) -> Result<usize> {
+    let collection = db.collection::<Task>("tasks");
+    let now = chrono::Utc::now().timestamp_millis();
+    let reap_script = queue::reap_script();
+    let prune_script = queue::prune_processing_list_script();
+    let mut reaped = 0;
+    
+    let lists: Vec<String> = redis::cmd("SMEMBERS")
+        .arg(queue::PROCESSING_LISTS_KEY)
+        .query_async(&mut conn)
+        .await?;
+    
+    for list in lists {
+        let payloads: Vec<String> = redis::cmd("LRANGE")
+            .arg(&list)
+            .arg(0)
+            .arg(-1)
+            .query_async(&mut conn)
+            .await?;
+        
+        if payloads.is_empty() {
+            let _: i32 = prune_script
+                .key(queue::PROCESSING_LISTS_KEY)
+                .key(&list)
+                .invoke_async(&mut conn)
+                .await?;
+            continue;
+        }
+        
+        for payload in payloads {
+            let Some(task_id) = queue::entry_task_id(&payload) else {
+                continue;
+            };
+            
+            let deadline: Option<f64> = redis::cmd("ZSCORE")
+                .arg(queue::LEASES_KEY)
+                .arg(&task_id)
+                .query_async(&mut conn)
+                .await?;
+            
+            match deadline {
+                None => {
+                    // Pas encore de lease (dequeue en cours ou crash juste après) :
+                    // on lui laisse un délai complet avant de la considérer perdue
+                    let _: () = redis::cmd("ZADD")
+                        .arg(queue::LEASES_KEY)
+                        .arg("NX")
+                        .arg(lease_deadline(visibility_timeout))
+                        .arg(&task_id)
+                        .query_async(&mut conn)
+                        .await?;
+                }
+                Some(deadline) if (deadline as i64) < now => {
+                    // La lane d'origine se lit dans MongoDB
+                    let stored = collection
+                        .find_one(mongodb::bson::doc! { "task_id": &task_id }, None)
+                        .await
+                        .context("Failed to load reaped task from MongoDB")?;
+                    let Some(task) = stored else {
+                        tracing::warn!(task_id = %task_id, "Reaped task not found in database, dropping it");
+                        let _: () = redis::pipe()
+                            .atomic()
+                            .lrem(&list, 1, &payload)
+                            .ignore()
+                            .zrem(queue::LEASES_KEY, &task_id)
+                            .ignore()
+                            .query_async(&mut conn)
+                            .await?;
+                        continue;
+                    };
+                    
+                    let requeued: i32 = reap_script
+                        .key(&list)
+                        .key(queue::LEASES_KEY)
+                        .key(queue::lane_key(&task.task_type, task.priority))
+                        .key(queue::notify_key(&task.task_type))
+                        .arg(&payload)
+                        .arg(&task.id)
+                        .invoke_async(&mut conn)
+                        .await?;
+                    
+                    if requeued > 0 {
+                        tracing::warn!(
+                            task_id = %task.id,
+                            processing_list = %list,
+                            "Lease expired, task returned to queue"
+                        );
+                        reaped += 1;
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    
+    Ok(reaped)
+}
+
+/// Récupère les tâches orphelines restées `processing` ; sert au démarrage
+/// du worker et à la commande `worker --recover`
+pub async fn recover_orphaned_tasks<C: redis::aio::ConnectionLike + Send>(
+    db: &Database,
+    conn: &mut C,
+    config: &EngineConfig,
+) -> Result<recovery::RecoveryReport> {
+    let report = recovery::recover_orphaned_tasks(db, conn, &config.retry_policy, config.recovery_stale_after)
+        .await
+        .context("Failed to recover orphaned tasks")?;
+    
+    tracing::info!(
+        examined = report.examined,
+        retried = report.retried,
+        failed = report.failed,
+        "Orphaned task recovery done"
+    );
+    
+    Ok(report)
+}
+
+/// Diffuse la progression d'une tâche jusqu'à la fin de son traitement : au
+/// plus une publication par `progress_interval`, toujours la dernière valeur,
+/// et une écriture MongoDB seulement aux checkpoints
+async fn report_progress(
+    pubsub: PubSubClient,
+    db: Database,
+    task_id: String,
+    mut progress_rx: watch::Receiver<f32>,
+    config: EngineConfig,
+) {
+    let mut throttle = ProgressThrottle::new(
+        config.progress_min_step,
+        config.progress_checkpoint_step,
+        *progress_rx.borrow(),
+    );
+    let mut pending = false;
+    
+    loop {
+        if !pending && progress_rx.changed().await.is_err() {
+            break;
+        }
+        let progress = *progress_rx.borrow_and_update();
+        report_progress_step(&mut throttle, &pubsub, &db, &task_id, progress).await;
+        pending = false;
+        
+        // Les valeurs reçues pendant l'intervalle sont fusionnées : seule la
+        // dernière est diffusée, tout de suite si le traitement se termine
+        let pause = sleep(config.progress_interval);
+        tokio::pin!(pause);
+        let closed = loop {
+            tokio::select! {
+                _ = &mut pause => break false,
+                changed = progress_rx.changed() => match changed {
+                    Ok(()) => pending = true,
+                    Err(_) => break true,
+                },
+            }
+        };
+        
+        if closed {
+            if pending {
+                let progress = *progress_rx.borrow();
+                report_progress_step(&mut throttle, &pubsub, &db, &task_id, progress).await;
+            }
+            break;
+        }
+    }
+}
+
+/// Publie une valeur de progression si elle avance assez, et l'écrit dans
+/// MongoDB si elle franchit un checkpoint
+async fn report_progress_step(
+    throttle: &mut ProgressThrottle,
+    pubsub: &PubSubClient,
+    db: &Database,
+    task_id: &str,
+    progress: f32,
+) {
+    if throttle.should_publish(progress) {
+        if let Err(e) = pubsub.publish_event(&TaskEvent::progress(task_id, progress)).await {
+            tracing::warn!(task_id = %task_id, progress = progress, error = %e, "Failed to publish progress");
+        }
+    }
+    
+    if throttle.is_checkpoint(progress) {
+        if let Err(e) = update_task_progress(db, task_id, progress).await {
+            tracing::error!(
+                task_id = %task_id,
+                progress = progress,
+                error = %e,
+                "Failed to update progress in MongoDB"
+            );
+        }
+    }
+}
+
+/// Écrit un checkpoint de progression dans MongoDB
 async fn update_task_progress(db: &Database, task_id: &str, progress: f32) -> Result<()> {
     let collection = db.collection::<Task>("tasks");
-    let filter = mongodb::bson::doc! { "task_id": task_id };
+    // Ne pas toucher une tâche sortie du traitement entre-temps
+    let filter = status_filter(task_id, &[TaskStatus::Processing]);
     let update = mongodb::bson::doc! {
         "$set": {
             "progress": progress,
-            "updated_at": mongodb::bson::DateTime::from_millis(chrono::Utc::now().timestamp_millis())
+            // Même format que les autres dates, comparées par la récupération
+            "updated_at": mongodb::bson::to_bson(&chrono::Utc::now())?
         }
     };
     
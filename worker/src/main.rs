@@ -1,9 +1,11 @@
+mod config;
 mod engine;
 mod processors;
 
-use engine::WorkerEngine;
+use config::EngineConfig;
+use engine::{QueueBinding, WorkerEngine};
 use processors::{AudioProcessor, ImageProcessor, TaskProcessor, VideoProcessor};
-use shared::TaskType;
+use shared::{queue, TaskType};
 use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -28,8 +30,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     } else {
         None
     };
+    // Commande d'administration : récupérer les tâches orphelines puis quitter
+    let recover_only = args.iter().any(|arg| arg == "--recover");
     
-    // Configuration - priorité aux args CLI, puis env, puis défaut
+    // Configuration - priorité aux args CLI, puis env, puis défaut.
+    // Un ou plusieurs types pondérés : "video" ou "video:1,image:3"
     let worker_type = worker_type_arg
         .or_else(|| std::env::var("WORKER_TYPE").ok())
         .unwrap_or_else(|| "video".to_string());
@@ -47,7 +52,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     tracing::info!("Starting Worker...");
     tracing::info!("Worker ID: {}", worker_id);
-    tracing::info!("Worker Type: {}", worker_type);
+    tracing::info!("Worker Queues: {}", worker_type);
     tracing::info!("MongoDB URI: {}", mongo_uri);
     tracing::info!("Redis URI: {}", redis_uri);
     tracing::info!("Output Dir: {}", output_dir);
@@ -72,7 +77,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to create Redis client");
     
     let mut conn = redis_client
-        .get_async_connection()
+        .get_multiplexed_async_connection()
         .await
         .expect("Failed to connect to Redis");
     
@@ -83,37 +88,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     tracing::info!("Connected to Redis");
     
-    // Create processor based on worker type
-    let (processor, task_type): (Arc<dyn TaskProcessor>, TaskType) = match worker_type.as_str() {
-        "video" => (
-            Arc::new(VideoProcessor::new(output_dir)),
-            TaskType::VideoCompression,
-        ),
-        "audio" => (
-            Arc::new(AudioProcessor::new(output_dir)),
-            TaskType::AudioProcessing,
-        ),
-        "image" => (
-            Arc::new(ImageProcessor::new(output_dir)),
-            TaskType::ImageOptimization,
-        ),
-        _ => panic!("Invalid WORKER_TYPE: {}. Must be 'video', 'audio', or 'image'", worker_type),
-    };
-    
-    tracing::info!("Processor initialized: {}", worker_type);
+    if recover_only {
+        engine::recover_orphaned_tasks(&mongo_db, &mut conn, &EngineConfig::from_env()).await?;
+        return Ok(());
+    }
+    
+    // Create one processor per served queue
+    let queue_weights = queue::parse_queue_weights(&worker_type).unwrap_or_else(|e| {
+        panic!("Invalid WORKER_TYPE '{}': {}. Expected e.g. 'video' or 'video:1,image:3'", worker_type, e)
+    });
+    
+    let queues: Vec<QueueBinding> = queue_weights
+        .into_iter()
+        .map(|(task_type, weight)| {
+            let processor: Arc<dyn TaskProcessor> = match task_type {
+                TaskType::VideoCompression => Arc::new(VideoProcessor::new(output_dir.clone())),
+                TaskType::AudioProcessing => Arc::new(AudioProcessor::new(output_dir.clone())),
+                TaskType::ImageOptimization => Arc::new(ImageProcessor::new(output_dir.clone())),
+            };
+            QueueBinding { task_type, weight, processor }
+        })
+        .collect();
+    
+    tracing::info!("Processors initialized: {}", worker_type);
     
     // Create and run worker engine
     let engine = WorkerEngine::new(
         redis_client,
         mongo_db,
-        processor,
-        task_type,
+        queues,
         worker_id,
-    );
+        EngineConfig::from_env(),
+    )
+    .await?;
+    
+    // Signal d'arrêt (SIGINT/SIGTERM) : stoppe le dequeue puis draine les tâches en cours
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        shared::utils::shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
     
     tracing::info!("Worker engine starting...");
     
-    engine.run().await?;
+    engine.run(shutdown_rx).await?;
     
     Ok(())
 }
@@ -1,4 +1,4 @@
-use super::{TaskProcessor, ProgressCallback, CancelFlag};
+use super::{wait_while_paused, TaskProcessor, ProgressCallback, CancelFlag, PauseFlag};
 use anyhow::Result;
 use shared::{Task, TaskStatus};
 use std::sync::atomic::Ordering;
@@ -20,10 +20,11 @@ impl TaskProcessor for AudioProcessor {
         task: &mut Task,
         progress_callback: ProgressCallback,
         cancel_flag: CancelFlag,
+        pause_flag: PauseFlag,
     ) -> Result<()> {
         tracing::info!(task_id = %task.id, "Starting audio processing");
         
-        task.update_status(TaskStatus::Processing);
+        task.update_status(TaskStatus::Processing)?;
         
         // Récupérer les options
         let format = task.media.metadata.get("audio_format")
@@ -50,10 +51,10 @@ impl TaskProcessor for AudioProcessor {
         );
         
         // Simuler le traitement avec vérification de cancellation
-        simulate_audio_processing(task, progress_callback, cancel_flag).await?;
+        simulate_audio_processing(task, progress_callback, cancel_flag, pause_flag).await?;
         
         task.output_path = Some(output_path);
-        task.update_status(TaskStatus::Completed);
+        task.update_status(TaskStatus::Completed)?;
         
         tracing::info!(task_id = %task.id, "Audio processing completed");
         
@@ -65,14 +66,18 @@ async fn simulate_audio_processing(
     task: &mut Task,
     progress_callback: ProgressCallback,
     cancel_flag: CancelFlag,
+    pause_flag: PauseFlag,
 ) -> Result<()> {
     use tokio::time::{sleep, Duration};
     
     for i in 0..=100 {
+        // Suspendre tant que la tâche est en pause
+        wait_while_paused(&pause_flag, &cancel_flag).await;
+        
         // Vérifier cancellation
         if cancel_flag.load(Ordering::Relaxed) {
             tracing::warn!(task_id = %task.id, "Task cancelled");
-            task.update_status(TaskStatus::Cancelled);
+            task.update_status(TaskStatus::Cancelled)?;
             return Err(anyhow::anyhow!("Task cancelled"));
         }
         
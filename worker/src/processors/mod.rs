@@ -1,7 +1,7 @@
 use shared::Task;
 use anyhow::Result;
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Type pour le callback de progression
 pub type ProgressCallback = Arc<dyn Fn(f32) + Send + Sync>;
@@ -9,6 +9,9 @@ pub type ProgressCallback = Arc<dyn Fn(f32) + Send + Sync>;
 /// Type pour le flag de cancellation
 pub type CancelFlag = Arc<AtomicBool>;
 
+/// Type pour le flag de pause : levé, le processor suspend son travail
+pub type PauseFlag = Arc<AtomicBool>;
+
 /// Trait pour tous les processeurs de tâches
 #[async_trait::async_trait]
 pub trait TaskProcessor: Send + Sync {
@@ -17,9 +20,18 @@ pub trait TaskProcessor: Send + Sync {
         task: &mut Task,
         progress_callback: ProgressCallback,
         cancel_flag: CancelFlag,
+        pause_flag: PauseFlag,
     ) -> Result<()>;
 }
 
+/// Point de suspension coopératif : attend tant que la pause est levée,
+/// en restant réactif à une annulation
+pub async fn wait_while_paused(pause_flag: &PauseFlag, cancel_flag: &CancelFlag) {
+    while pause_flag.load(Ordering::Relaxed) && !cancel_flag.load(Ordering::Relaxed) {
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    }
+}
+
 pub mod video_processor;
 pub mod audio_processor;
 pub mod image_processor;